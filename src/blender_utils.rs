@@ -1,88 +1,203 @@
-use std::collections::HashMap;
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use regex::Regex;
+use reqwest::Url;
 use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
 
-use crate::BlenderVersion;
+use crate::{BlenderVersion, LocalBlenderVersion, Release};
 
+/// Parses Blender archive and directory names, with or without a leading url.
+///
+/// Builder names look like `blender-4.3.0-alpha+main.2f6cf1a2eac8-linux.x86_64-release.tar.xz`,
+/// where the branch may contain dots and dashes (`temp-foo`, `PR12345`) and the extension is
+/// `tar.xz`, `zip` or `dmg`, or missing for an extracted directory. Official releases look like
+/// `blender-4.2.3-linux-x64.tar.xz`.
 pub struct BlenderMatcher {
     main_pattern: Regex,
-    extracted_pattern: Regex,
+    release_pattern: Regex,
 }
 
 impl BlenderMatcher {
     pub fn new() -> Self {
         let main_pattern = Regex::new(
-            r#"blender-(?<version>\d.\d.\d+)-(?<release>\w+)\+(?<branch>.+?)-(?<os>.+)-"#,
+            r#"^blender-(?<version>\d+\.\d+\.\d+)-(?<release>[a-z]+)\+(?<branch>.+)\.(?<hash>[0-9a-f]+)-(?<os>[a-z]+)\.(?<arch>[a-z0-9_]+)-release(?:\.(?<extension>tar\.xz|tar\.bz2|zip|dmg))?$"#,
         )
         .unwrap();
 
-        let extracted_pattern = Regex::new(r#"blender-(?<version>\d.\d.\d+)-(?<os>.+)"#).unwrap();
+        let release_pattern = Regex::new(
+            r#"^blender-(?<version>\d+\.\d+\.\d+)-(?<os>[a-z]+)-(?<arch>[a-z0-9_]+)(?:\.(?<extension>tar\.xz|tar\.bz2|zip|dmg|msi))?$"#,
+        )
+        .unwrap();
 
         BlenderMatcher {
             main_pattern,
-            extracted_pattern,
+            release_pattern,
         }
     }
 
     pub fn match_str(&self, blender_str: &str) -> Option<BlenderVersion> {
-        if let Some(captures) = self.main_pattern.captures(blender_str.into()) {
-            let version = captures.name("version");
-            let release = captures.name("release");
-            let branch = captures.name("branch");
-            let os = captures.name("os");
-
-            if let (Some(version), Some(release), Some(branch), Some(os)) =
-                (version, release, branch, os)
-            {
-                let blender_version = BlenderVersion {
-                    version: version.as_str().to_owned(),
-                    release: release.as_str().to_owned(),
-                    branch: branch.as_str().to_owned(),
-                    os: os.as_str().to_owned(),
-                    link: "".to_owned(),
-                };
-
-                return Some(blender_version);
-            }
-        } else if let Some(captures) = self.extracted_pattern.captures(blender_str) {
-            let version = captures.name("version");
-            let os = captures.name("os");
-
-            if let (Some(version), Some(os)) = (version, os) {
-                let blender_version = BlenderVersion {
-                    version: version.as_str().to_owned(),
-                    os: os.as_str().to_owned(),
-                    release: "stable".to_owned(),
-                    branch: String::new(),
-                    link: String::new(),
-                };
-
-                return Some(blender_version);
-            }
+        let name = blender_str.rsplit('/').next()?;
+
+        if let Some(captures) = self.main_pattern.captures(name) {
+            let version = captures.name("version")?.as_str().parse().ok()?;
+            let release = captures.name("release")?.as_str().parse().ok()?;
+
+            let blender_version = BlenderVersion {
+                version,
+                release,
+                branch: captures.name("branch")?.as_str().to_owned(),
+                hash: captures.name("hash")?.as_str().to_owned(),
+                os: normalize_os(captures.name("os")?.as_str()),
+                arch: normalize_arch(captures.name("arch")?.as_str()),
+                extension: captures
+                    .name("extension")
+                    .map(|extension| extension.as_str().to_owned())
+                    .unwrap_or_default(),
+                ..Default::default()
+            };
+
+            return Some(blender_version);
+        } else if let Some(captures) = self.release_pattern.captures(name) {
+            let version = captures.name("version")?.as_str().parse().ok()?;
+
+            let blender_version = BlenderVersion {
+                version,
+                release: Release::Stable,
+                os: normalize_os(captures.name("os")?.as_str()),
+                arch: normalize_arch(captures.name("arch")?.as_str()),
+                extension: captures
+                    .name("extension")
+                    .map(|extension| extension.as_str().to_owned())
+                    .unwrap_or_default(),
+                ..Default::default()
+            };
+
+            return Some(blender_version);
         }
 
         None
     }
 }
 
+impl Default for BlenderMatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Platform name shared by builder and release listings: "linux", "windows" or "macos".
+pub fn normalize_os(os: &str) -> String {
+    match os.to_lowercase().as_str() {
+        "darwin" | "macos" | "osx" => "macos".to_owned(),
+        "win" | "win32" | "win64" | "windows" => "windows".to_owned(),
+        other => other.to_owned(),
+    }
+}
+
+/// Architecture name shared by builder and release listings: "x64" or "arm64".
+pub fn normalize_arch(arch: &str) -> String {
+    match arch.to_lowercase().as_str() {
+        "x86_64" | "amd64" | "x64" => "x64".to_owned(),
+        "aarch64" | "arm64" => "arm64".to_owned(),
+        other => other.to_owned(),
+    }
+}
+
+/// Platform of the machine the manager runs on.
+pub fn host_os() -> String {
+    normalize_os(std::env::consts::OS)
+}
+
+/// Architecture of the machine the manager runs on.
+pub fn host_arch() -> String {
+    normalize_arch(std::env::consts::ARCH)
+}
+
+/// Newest build of a series in a listing returned by [`select`].
+pub fn latest_in_series<'a>(
+    versions: &'a [BlenderVersion],
+    series: &str,
+) -> Option<&'a BlenderVersion> {
+    versions
+        .iter()
+        .filter(|version| version.version.in_series(series))
+        .max_by_key(|version| (version.version, version.release))
+}
+
+/// How a remote build relates to the local installs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteStatus {
+    Installed,
+    /// A newer build of an installed version and branch, by build date or, when either date
+    /// is unknown, a different hash.
+    Update,
+    New,
+}
+
+pub fn remote_status(remote: &BlenderVersion, locals: &[LocalBlenderVersion]) -> RemoteStatus {
+    let remote_dir = remote.dir_name();
+    if locals.iter().any(|local| local.path.ends_with(&remote_dir)) {
+        return RemoteStatus::Installed;
+    }
+
+    let outdated = locals.iter().any(|local| {
+        let local = &local.blender_version;
+
+        let newer = match (local.build_date, remote.build_date) {
+            (Some(local), Some(remote)) => remote > local,
+            _ => local.hash != remote.hash,
+        };
+
+        local.version == remote.version
+            && local.branch == remote.branch
+            && local.arch == remote.arch
+            && newer
+    });
+
+    match outdated {
+        true => RemoteStatus::Update,
+        false => RemoteStatus::New,
+    }
+}
+
 fn filter_latest(versions: Vec<BlenderVersion>) -> Vec<BlenderVersion> {
     let mut result = HashMap::new();
 
     for version in versions.into_iter().rev() {
-        let key = (version.version.clone(), version.release.clone());
+        let key = (
+            version.version,
+            version.release,
+            version.branch.clone(),
+            version.os.clone(),
+            version.arch.clone(),
+        );
 
-        if result.contains_key(&(version.version.clone(), "stable".to_string())) {
-            continue;
-        } else if result.contains_key(&key) {
+        let stable = (
+            version.version,
+            Release::Stable,
+            version.branch.clone(),
+            version.os.clone(),
+            version.arch.clone(),
+        );
+        if result.contains_key(&stable) {
             continue;
-        } else {
-            result.insert(key, version);
         }
+
+        result.entry(key).or_insert(version);
     }
 
-    let mut result: Vec<BlenderVersion> = result.into_values().into_iter().collect();
-    result.sort_by(|a, b| b.version.partial_cmp(&a.version).unwrap());
+    let mut result: Vec<BlenderVersion> = result.into_values().collect();
+    result.sort_by(|a, b| {
+        (b.version, b.release)
+            .cmp(&(a.version, a.release))
+            .then_with(|| (&a.branch, &a.os, &a.arch).cmp(&(&b.branch, &b.os, &b.arch)))
+    });
     result
 }
 
@@ -90,8 +205,7 @@ pub fn select(body: String) -> Result<Vec<BlenderVersion>, String> {
     let document = Html::parse_document(&body);
 
     let selector =
-        Selector::parse("[data-platform='linux'] li:not([style='display:none;']) a:first-child")
-            .unwrap();
+        Selector::parse("[data-platform] li:not([style='display:none;']) a:first-child").unwrap();
 
     let mut links = Vec::with_capacity(600);
 
@@ -110,3 +224,692 @@ pub fn select(body: String) -> Result<Vec<BlenderVersion>, String> {
 
     Ok(links)
 }
+
+/// Series of the `BlenderX.Y/` directories in the release index, newest first.
+pub fn release_series(body: &str) -> Vec<String> {
+    let document = Html::parse_document(body);
+    let selector = Selector::parse("a[href]").unwrap();
+    let pattern = Regex::new(r"^Blender(?<major>\d+)\.(?<minor>\d+)/$").unwrap();
+
+    let mut series: Vec<(u32, u32)> = document
+        .select(&selector)
+        .filter_map(|el| {
+            let captures = pattern.captures(el.attr("href")?)?;
+            Some((
+                captures.name("major")?.as_str().parse().ok()?,
+                captures.name("minor")?.as_str().parse().ok()?,
+            ))
+        })
+        .collect();
+
+    series.sort_by_key(|series| Reverse(*series));
+    series.dedup();
+    series
+        .into_iter()
+        .map(|(major, minor)| format!("{major}.{minor}"))
+        .collect()
+}
+
+/// Parses the archives in a `BlenderX.Y/` release directory index, with links resolved
+/// against `base`.
+pub fn parse_release_index(body: &str, base: &Url) -> Vec<BlenderVersion> {
+    let document = Html::parse_document(body);
+    let selector = Selector::parse("a[href]").unwrap();
+    let matcher = BlenderMatcher::new();
+    let listing_pattern =
+        Regex::new(r"^\s*(?<date>\d{2}-[A-Za-z]{3}-\d{4} \d{2}:\d{2})\s+(?<size>\S+)").unwrap();
+
+    let links = document
+        .select(&selector)
+        .filter_map(|el| {
+            let href = el.attr("href")?;
+            let mut blender_version = matcher.match_str(href)?;
+
+            // installers can't be extracted
+            if blender_version.extension.is_empty() || blender_version.extension == "msi" {
+                return None;
+            }
+
+            blender_version.link = base.join(href).ok()?.to_string();
+
+            // autoindex pages list "15-Oct-2024 09:46    357613092" after each link
+            if let Some(text) = el.next_sibling().and_then(|node| node.value().as_text()) {
+                if let Some(captures) = listing_pattern.captures(text) {
+                    blender_version.build_date = parse_index_date(&captures["date"]);
+                    blender_version.size = captures["size"].parse().ok();
+                }
+            }
+
+            Some(blender_version)
+        })
+        .collect();
+
+    filter_latest(links)
+}
+
+/// Seconds since the unix epoch of an autoindex date like "15-Oct-2024 09:46", in UTC.
+fn parse_index_date(date: &str) -> Option<u64> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    let (day, rest) = date.split_once('-')?;
+    let (month, rest) = rest.split_once('-')?;
+    let (year, time) = rest.split_once(' ')?;
+    let (hour, minute) = time.split_once(':')?;
+
+    let month = MONTHS.iter().position(|name| *name == month)? as i64 + 1;
+    unix_time(
+        year.parse().ok()?,
+        month,
+        day.parse().ok()?,
+        hour.parse().ok()?,
+        minute.parse().ok()?,
+        0,
+    )
+}
+
+/// Seconds since the unix epoch of a UTC date like "2024-10-15T09:46:00Z".
+fn parse_feed_date(date: &str) -> Option<u64> {
+    let (date, time) = date.trim_end_matches('Z').split_once('T')?;
+    let mut date = date.splitn(3, '-').map(|part| part.parse().ok());
+    let mut time = time.splitn(3, ':').map(|part| part.parse().ok());
+
+    unix_time(
+        date.next()??,
+        date.next()??,
+        date.next()??,
+        time.next()??,
+        time.next()??,
+        time.next()??,
+    )
+}
+
+fn unix_time(year: i64, month: i64, day: i64, hour: i64, minute: i64, second: i64) -> Option<u64> {
+    // days from the civil date, counting years from March so the leap day comes last
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    u64::try_from(days * 86_400 + hour * 3_600 + minute * 60 + second).ok()
+}
+
+/// One file in the builder's JSON listing (`?format=json&v=1`).
+#[derive(Debug, Deserialize)]
+struct BuilderEntry {
+    url: String,
+    version: String,
+    risk_id: String,
+    branch: String,
+    hash: String,
+    platform: String,
+    architecture: String,
+    file_mtime: u64,
+    file_name: String,
+    file_size: u64,
+    file_extension: String,
+}
+
+/// Parses the builder's JSON listing of every platform, skipping checksum files.
+pub fn parse_json(body: &str) -> Result<Vec<BlenderVersion>, String> {
+    let entries: Vec<BuilderEntry> = serde_json::from_str(body).map_err(|err| err.to_string())?;
+
+    let links: Vec<BlenderVersion> = entries
+        .into_iter()
+        .filter(|entry| entry.file_extension != "sha256")
+        .filter(|entry| entry.file_name.starts_with("blender-"))
+        .filter_map(|entry| {
+            let extension = match entry.file_name.ends_with(".tar.xz") {
+                true => "tar.xz".to_owned(),
+                false => entry.file_extension,
+            };
+
+            Some(BlenderVersion {
+                version: entry.version.parse().ok()?,
+                release: entry.risk_id.parse().ok()?,
+                branch: entry.branch,
+                hash: entry.hash,
+                os: normalize_os(&entry.platform),
+                arch: normalize_arch(&entry.architecture),
+                extension,
+                build_date: Some(entry.file_mtime),
+                link: entry.url,
+                size: Some(entry.file_size),
+                ..Default::default()
+            })
+        })
+        .collect();
+
+    Ok(filter_latest(links))
+}
+
+/// One release in a GitHub-style release feed.
+#[derive(Debug, Deserialize)]
+struct FeedRelease {
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    assets: Vec<FeedAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FeedAsset {
+    name: String,
+    size: u64,
+    browser_download_url: String,
+    /// "sha256:<hex>" when the feed publishes it.
+    digest: Option<String>,
+    updated_at: Option<String>,
+}
+
+/// Parses a GitHub-style release feed, keeping the assets whose name matches `pattern`.
+///
+/// The pattern needs `version` and `os` groups and may have `arch` (x64 when missing),
+/// `release` (stable, or beta for prereleases) and `extension` groups. Builds get `branch`
+/// as their branch.
+pub fn parse_feed(
+    body: &str,
+    pattern: &Regex,
+    branch: &str,
+) -> Result<Vec<BlenderVersion>, String> {
+    let releases: Vec<FeedRelease> = serde_json::from_str(body).map_err(|err| err.to_string())?;
+
+    let links = releases
+        .into_iter()
+        .flat_map(|release| {
+            let prerelease = release.prerelease;
+            release
+                .assets
+                .into_iter()
+                .map(move |asset| (prerelease, asset))
+        })
+        .filter_map(|(prerelease, asset)| {
+            let captures = pattern.captures(&asset.name)?;
+
+            let release = match captures.name("release") {
+                Some(release) => release.as_str().to_lowercase().parse().ok()?,
+                None if prerelease => Release::Beta,
+                None => Release::Stable,
+            };
+
+            let extension = match captures.name("extension") {
+                Some(extension) => extension.as_str().to_owned(),
+                None => ["tar.xz", "zip", "dmg"]
+                    .into_iter()
+                    .find(|extension| asset.name.ends_with(&format!(".{extension}")))?
+                    .to_owned(),
+            };
+
+            let sha256 = asset
+                .digest
+                .as_deref()
+                .and_then(|digest| digest.strip_prefix("sha256:"))
+                .map(str::to_lowercase);
+
+            Some(BlenderVersion {
+                version: captures.name("version")?.as_str().parse().ok()?,
+                release,
+                branch: branch.to_owned(),
+                os: normalize_os(captures.name("os")?.as_str()),
+                arch: captures
+                    .name("arch")
+                    .map(|arch| normalize_arch(arch.as_str()))
+                    .unwrap_or_else(|| "x64".to_owned()),
+                extension,
+                build_date: asset.updated_at.as_deref().and_then(parse_feed_date),
+                link: asset.browser_download_url,
+                size: Some(asset.size),
+                sha256,
+                ..Default::default()
+            })
+        })
+        .collect();
+
+    Ok(filter_latest(links))
+}
+
+/// Executable of the install at `path`: `blender`, or for forks the one named like the
+/// directory's prefix, e.g. `bforartists` in `Bforartists-4.2.0-Linux`.
+pub fn executable(path: &Path) -> PathBuf {
+    let dir_name = path.file_name().unwrap_or_default().to_string_lossy();
+    find_executable(path, &dir_name).unwrap_or_else(|| path.join("blender"))
+}
+
+/// Looks for the executable of the build `dir_name` in `dir`.
+pub fn find_executable(dir: &Path, dir_name: &str) -> Option<PathBuf> {
+    let product = dir_name
+        .split('-')
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+
+    let candidates = [
+        "blender".to_owned(),
+        "blender.exe".to_owned(),
+        product.clone(),
+        format!("{product}.exe"),
+    ];
+
+    candidates
+        .iter()
+        .filter(|name| !name.is_empty())
+        .map(|name| dir.join(name))
+        .find(|exe| exe.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Version;
+
+    fn find<'a>(versions: &'a [BlenderVersion], os: &str, arch: &str) -> &'a BlenderVersion {
+        versions
+            .iter()
+            .find(|version| version.os == os && version.arch == arch)
+            .unwrap_or_else(|| panic!("no {os} {arch} build"))
+    }
+
+    #[test]
+    fn json_listing_is_parsed() {
+        let versions = parse_json(include_str!("../tests/fixtures/builder_daily.json")).unwrap();
+
+        let linux = find(&versions, "linux", "x64");
+        assert_eq!(linux.version, Version::new(4, 3, 0));
+        assert_eq!(linux.release, Release::Alpha);
+        assert_eq!(linux.branch, "main");
+        assert_eq!(linux.hash, "2f6cf1a2eac8");
+        assert_eq!(linux.extension, "tar.xz");
+        assert_eq!(linux.build_date, Some(1728976000));
+        assert_eq!(linux.size, Some(360123456));
+        assert_eq!(
+            linux.dir_name(),
+            "blender-4.3.0-alpha+main.2f6cf1a2eac8-linux.x86_64-release"
+        );
+
+        let candidate = versions
+            .iter()
+            .find(|version| version.version == Version::new(4, 2, 4))
+            .unwrap();
+        assert_eq!(candidate.release, Release::Candidate);
+        assert_eq!(candidate.branch, "v42");
+    }
+
+    #[test]
+    fn json_platforms_are_normalized() {
+        let versions = parse_json(include_str!("../tests/fixtures/builder_daily.json")).unwrap();
+
+        assert_eq!(find(&versions, "windows", "x64").extension, "zip");
+        assert_eq!(find(&versions, "macos", "arm64").extension, "dmg");
+        assert_eq!(find(&versions, "macos", "x64").extension, "dmg");
+    }
+
+    #[test]
+    fn json_checksums_are_skipped() {
+        let versions = parse_json(include_str!("../tests/fixtures/builder_daily.json")).unwrap();
+
+        assert_eq!(versions.len(), 5);
+        assert!(versions
+            .iter()
+            .all(|version| !version.link.ends_with(".sha256")));
+    }
+
+    #[test]
+    fn json_keeps_every_branch() {
+        let versions = parse_json(include_str!("../tests/fixtures/builder_patch.json")).unwrap();
+
+        let mut branches: Vec<&str> = versions
+            .iter()
+            .map(|version| version.branch.as_str())
+            .collect();
+        branches.sort();
+        assert_eq!(branches, ["PR123456", "PR123789", "cycles-oneapi"]);
+    }
+
+    #[test]
+    fn html_listing_is_scraped() {
+        let body = include_str!("../tests/fixtures/builder_daily.html").to_owned();
+        let versions = select(body).unwrap();
+
+        // hidden rows and checksum links are left out
+        assert_eq!(versions.len(), 3);
+        assert!(versions
+            .iter()
+            .all(|version| version.version == Version::new(4, 3, 0)));
+
+        let linux = find(&versions, "linux", "x64");
+        assert_eq!(linux.hash, "2f6cf1a2eac8");
+        assert!(linux.link.starts_with("https://cdn.builder.blender.org/"));
+        find(&versions, "windows", "x64");
+        find(&versions, "macos", "arm64");
+    }
+
+    #[test]
+    fn matcher_parses_every_name_shape() {
+        // name, version, release, branch, hash, os, arch, extension
+        let cases = [
+            (
+                "blender-4.3.0-alpha+main.2f6cf1a2eac8-linux.x86_64-release.tar.xz",
+                "4.3.0", Release::Alpha, "main", "2f6cf1a2eac8", "linux", "x64", "tar.xz",
+            ),
+            (
+                "blender-4.3.0-alpha+main.2f6cf1a2eac8-windows.amd64-release.zip",
+                "4.3.0", Release::Alpha, "main", "2f6cf1a2eac8", "windows", "x64", "zip",
+            ),
+            (
+                "blender-4.3.0-alpha+main.2f6cf1a2eac8-darwin.arm64-release.dmg",
+                "4.3.0", Release::Alpha, "main", "2f6cf1a2eac8", "macos", "arm64", "dmg",
+            ),
+            (
+                "https://cdn.builder.blender.org/download/daily/blender-4.2.4-candidate+v42.0a1b2c3d4e5f-linux.x86_64-release.tar.xz",
+                "4.2.4", Release::Candidate, "v42", "0a1b2c3d4e5f", "linux", "x64", "tar.xz",
+            ),
+            (
+                "blender-4.4.0-alpha+temp-geometry.nodes-v2.1234567890ab-linux.x86_64-release.tar.xz",
+                "4.4.0", Release::Alpha, "temp-geometry.nodes-v2", "1234567890ab", "linux", "x64", "tar.xz",
+            ),
+            (
+                "blender-4.4.0-alpha+PR12345.abcdef123456-windows.arm64-release.zip",
+                "4.4.0", Release::Alpha, "PR12345", "abcdef123456", "windows", "arm64", "zip",
+            ),
+            (
+                "blender-4.3.0-alpha+main.2f6cf1a2eac8-linux.x86_64-release",
+                "4.3.0", Release::Alpha, "main", "2f6cf1a2eac8", "linux", "x64", "",
+            ),
+            (
+                "blender-4.12.0-beta+main.2f6cf1a2eac8-linux.x86_64-release.tar.xz",
+                "4.12.0", Release::Beta, "main", "2f6cf1a2eac8", "linux", "x64", "tar.xz",
+            ),
+            (
+                "blender-10.0.1-rc+main.2f6cf1a2eac8-linux.x86_64-release.tar.xz",
+                "10.0.1", Release::Rc, "main", "2f6cf1a2eac8", "linux", "x64", "tar.xz",
+            ),
+            (
+                "blender-4.2.3-linux-x64.tar.xz",
+                "4.2.3", Release::Stable, "", "", "linux", "x64", "tar.xz",
+            ),
+            (
+                "blender-4.2.3-macos-arm64.dmg",
+                "4.2.3", Release::Stable, "", "", "macos", "arm64", "dmg",
+            ),
+            (
+                "blender-4.2.3-windows-x64.zip",
+                "4.2.3", Release::Stable, "", "", "windows", "x64", "zip",
+            ),
+            (
+                "blender-4.2.3-linux-x64",
+                "4.2.3", Release::Stable, "", "", "linux", "x64", "",
+            ),
+        ];
+
+        let matcher = BlenderMatcher::new();
+
+        for (name, version, release, branch, hash, os, arch, extension) in cases {
+            let parsed = matcher
+                .match_str(name)
+                .unwrap_or_else(|| panic!("{name} didn't match"));
+
+            assert_eq!(parsed.version, version.parse().unwrap(), "{name}");
+            assert_eq!(parsed.release, release, "{name}");
+            assert_eq!(parsed.branch, branch, "{name}");
+            assert_eq!(parsed.hash, hash, "{name}");
+            assert_eq!(parsed.os, os, "{name}");
+            assert_eq!(parsed.arch, arch, "{name}");
+            assert_eq!(parsed.extension, extension, "{name}");
+        }
+    }
+
+    #[test]
+    fn matcher_rejects_other_files() {
+        let matcher = BlenderMatcher::new();
+
+        for name in [
+            "blender-4.3.0-alpha+main.2f6cf1a2eac8-linux.x86_64-release.tar.xz.sha256",
+            "blender-4.2.3.sha256",
+            "blender-4.2.3.md5",
+            "Bforartists-4.2.0-Linux.tar.xz",
+            "blender-current",
+            "blender-4.2",
+        ] {
+            assert!(matcher.match_str(name).is_none(), "{name}");
+        }
+    }
+
+    #[test]
+    fn versions_order_numerically() {
+        let version = |s: &str| s.parse::<Version>().unwrap();
+
+        assert!(version("4.10.0") > version("4.9.0"));
+        assert!(version("10.0.0") > version("9.99.99"));
+        assert!(version("4.2.10") > version("4.2.9"));
+        assert!(Release::Stable > Release::Candidate);
+        assert!(Release::Beta > Release::Alpha);
+    }
+
+    #[test]
+    fn release_series_are_listed_newest_first() {
+        let series = release_series(include_str!("../tests/fixtures/release_index.html"));
+
+        assert_eq!(series, ["4.10", "4.2", "4.1", "3.6", "2.93", "2.79"]);
+    }
+
+    #[test]
+    fn release_index_is_parsed() {
+        let base = Url::parse("https://download.blender.org/release/Blender4.2/").unwrap();
+        let versions =
+            parse_release_index(include_str!("../tests/fixtures/release_4.2.html"), &base);
+
+        let linux = find(&versions, "linux", "x64");
+        assert_eq!(linux.version, Version::new(4, 2, 3));
+        assert_eq!(linux.release, Release::Stable);
+        assert_eq!(
+            linux.link,
+            "https://download.blender.org/release/Blender4.2/blender-4.2.3-linux-x64.tar.xz"
+        );
+        // 15-Oct-2024 09:46 UTC
+        assert_eq!(linux.build_date, Some(1728985560));
+        assert_eq!(linux.size, Some(357613092));
+
+        assert_eq!(find(&versions, "macos", "arm64").extension, "dmg");
+        assert_eq!(find(&versions, "macos", "x64").size, Some(341090112));
+    }
+
+    #[test]
+    fn release_index_skips_installers_and_checksums() {
+        let base = Url::parse("https://download.blender.org/release/Blender4.2/").unwrap();
+        let versions =
+            parse_release_index(include_str!("../tests/fixtures/release_4.2.html"), &base);
+
+        // the msi, msix, md5 and sha256 files aren't builds
+        let mut names: Vec<&str> = versions.iter().map(|version| version.file_name()).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "blender-4.2.2-linux-x64.tar.xz",
+                "blender-4.2.3-linux-x64.tar.xz",
+                "blender-4.2.3-macos-arm64.dmg",
+                "blender-4.2.3-macos-x64.dmg",
+                "blender-4.2.3-windows-x64.zip",
+            ]
+        );
+    }
+
+    #[test]
+    fn index_dates_are_utc() {
+        assert_eq!(parse_index_date("01-Jan-1970 00:00"), Some(0));
+        assert_eq!(parse_index_date("29-Feb-2024 12:30"), Some(1709209800));
+        assert_eq!(parse_index_date("15-Foo-2024 09:46"), None);
+    }
+
+    const UPBGE_PATTERN: &str = r"^upbge-v?(?<version>\d+\.\d+\.\d+)-(?<os>linux|windows|macos)-(?<arch>x86_64|arm64)\.(?<extension>tar\.xz|zip|dmg)$";
+
+    #[test]
+    fn feed_assets_are_mapped_by_pattern() {
+        let pattern = Regex::new(UPBGE_PATTERN).unwrap();
+        let versions = parse_feed(
+            include_str!("../tests/fixtures/feed_upbge.json"),
+            &pattern,
+            "upbge",
+        )
+        .unwrap();
+
+        // the checksum asset doesn't match the pattern
+        let mut names: Vec<&str> = versions.iter().map(|version| version.file_name()).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "upbge-0.36.1-linux-x86_64.tar.xz",
+                "upbge-0.36.1-macos-arm64.dmg",
+                "upbge-0.36.1-windows-x86_64.zip",
+                "upbge-0.40.0-linux-x86_64.tar.xz",
+            ]
+        );
+
+        let windows = find(&versions, "windows", "x64");
+        assert_eq!(windows.version, Version::new(0, 36, 1));
+        assert_eq!(windows.branch, "upbge");
+        assert_eq!(windows.extension, "zip");
+        assert_eq!(windows.size, Some(390148765));
+        // 2024-10-15T09:47:00Z
+        assert_eq!(windows.build_date, Some(1728985620));
+        assert_eq!(windows.dir_name(), "upbge-0.36.1-windows-x86_64");
+    }
+
+    #[test]
+    fn feed_prereleases_are_betas() {
+        let pattern = Regex::new(UPBGE_PATTERN).unwrap();
+        let versions = parse_feed(
+            include_str!("../tests/fixtures/feed_upbge.json"),
+            &pattern,
+            "upbge",
+        )
+        .unwrap();
+
+        for version in versions {
+            let expected = match version.version == Version::new(0, 40, 0) {
+                true => Release::Beta,
+                false => Release::Stable,
+            };
+            assert_eq!(version.release, expected, "{}", version.file_name());
+        }
+    }
+
+    #[test]
+    fn feed_digests_are_checksums() {
+        let pattern = Regex::new(UPBGE_PATTERN).unwrap();
+        let versions = parse_feed(
+            include_str!("../tests/fixtures/feed_upbge.json"),
+            &pattern,
+            "upbge",
+        )
+        .unwrap();
+
+        let linux = versions
+            .iter()
+            .find(|version| version.version == Version::new(0, 36, 1) && version.os == "linux")
+            .unwrap();
+        assert_eq!(
+            linux.sha256.as_deref(),
+            Some("60937844b3ce34e34beda7bd4d622644433abddcc34ebeeb437e47f0994b8756")
+        );
+
+        // a null or missing digest leaves the checksum to the .sha256 file
+        assert_eq!(find(&versions, "windows", "x64").sha256, None);
+        assert_eq!(find(&versions, "macos", "arm64").sha256, None);
+    }
+
+    #[test]
+    fn feed_assets_without_arch_are_x64() {
+        let pattern = Regex::new(
+            r"^Bforartists-(?<version>\d+\.\d+\.\d+)-(?<os>Linux|Windows|macOS)\.(?<extension>tar\.xz|zip|dmg)$",
+        )
+        .unwrap();
+        let versions = parse_feed(
+            include_str!("../tests/fixtures/feed_bforartists.json"),
+            &pattern,
+            "bforartists",
+        )
+        .unwrap();
+
+        assert_eq!(versions.len(), 3);
+        assert!(versions.iter().all(|version| version.arch == "x64"));
+        assert_eq!(find(&versions, "linux", "x64").extension, "tar.xz");
+        assert_eq!(find(&versions, "macos", "x64").extension, "dmg");
+        assert_eq!(
+            find(&versions, "linux", "x64").dir_name(),
+            "Bforartists-4.2.0-Linux"
+        );
+    }
+
+    fn local(dir_name: &str, build_date: Option<u64>) -> LocalBlenderVersion {
+        let mut blender_version = BlenderMatcher::new().match_str(dir_name).unwrap();
+        blender_version.build_date = build_date;
+
+        LocalBlenderVersion {
+            blender_version,
+            created: String::new(),
+            installed: 0,
+            path: PathBuf::from("/opt/blender").join(dir_name),
+            protected: false,
+            default: false,
+        }
+    }
+
+    fn remote(file_name: &str, build_date: Option<u64>) -> BlenderVersion {
+        let mut version = BlenderMatcher::new().match_str(file_name).unwrap();
+        version.link = format!("https://cdn.builder.blender.org/download/daily/{file_name}");
+        version.build_date = build_date;
+        version
+    }
+
+    #[test]
+    fn remote_status_compares_build_dates() {
+        let locals = [local(
+            "blender-4.3.0-alpha+main.2f6cf1a2eac8-linux.x86_64-release",
+            Some(1_728_976_000),
+        )];
+
+        let installed = remote(
+            "blender-4.3.0-alpha+main.2f6cf1a2eac8-linux.x86_64-release.tar.xz",
+            Some(1_728_976_000),
+        );
+        assert_eq!(remote_status(&installed, &locals), RemoteStatus::Installed);
+
+        let newer = remote(
+            "blender-4.3.0-alpha+main.3a7b9c0d1e2f-linux.x86_64-release.tar.xz",
+            Some(1_729_062_400),
+        );
+        assert_eq!(remote_status(&newer, &locals), RemoteStatus::Update);
+
+        // an older build, e.g. from the archive, doesn't replace the install
+        let older = remote(
+            "blender-4.3.0-alpha+main.1c2d3e4f5a6b-linux.x86_64-release.tar.xz",
+            Some(1_728_889_600),
+        );
+        assert_eq!(remote_status(&older, &locals), RemoteStatus::New);
+
+        let other_branch = remote(
+            "blender-4.3.0-alpha+npr.3a7b9c0d1e2f-linux.x86_64-release.tar.xz",
+            Some(1_729_062_400),
+        );
+        assert_eq!(remote_status(&other_branch, &locals), RemoteStatus::New);
+    }
+
+    #[test]
+    fn remote_status_falls_back_to_hashes() {
+        let locals = [local(
+            "blender-4.3.0-alpha+main.2f6cf1a2eac8-linux.x86_64-release",
+            None,
+        )];
+
+        let different = remote(
+            "blender-4.3.0-alpha+main.3a7b9c0d1e2f-linux.x86_64-release.tar.xz",
+            Some(1_729_062_400),
+        );
+        assert_eq!(remote_status(&different, &locals), RemoteStatus::Update);
+    }
+}
@@ -0,0 +1,528 @@
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use tokio::signal::unix::{signal, SignalKind};
+
+use crate::{
+    blender_utils::latest_in_series,
+    config::Config,
+    desktop::update_entries,
+    getter::{get_links, get_series_releases},
+    launcher::blender_command,
+    links::update_links,
+    manager::DownloadManager,
+    prune::{has_retention, prune, PruneReport},
+    source::{Source, SourceKind},
+    tui::{check_downloaded, parse_downloaded, remove_install, JobEvent, Message},
+    BlenderVersion, LocalBlenderVersion, Retry, Version,
+};
+
+#[derive(Parser)]
+#[command(version, about = "Download and manage Blender builds")]
+pub struct Cli {
+    /// Print results and errors as JSON objects
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Platform to list and install builds for: linux, windows, macos or all
+    #[arg(long, global = true)]
+    pub platform: Option<String>,
+
+    /// Architecture to list and install builds for: x64, arm64 or all
+    #[arg(long, global = true)]
+    pub arch: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// List the latest builds available on the remote
+    ListRemote,
+    /// List the builds installed in the configured path
+    ListLocal,
+    /// Download and extract the latest build of a version, e.g. 4.2.1
+    Install {
+        version: Version,
+        /// Source to install from, needed for forks listed by feed sources
+        #[arg(long)]
+        source: Option<String>,
+    },
+    /// Remove an installed build by directory name or version
+    Remove { name: String },
+    /// Launch an installed build by directory name or version
+    Launch {
+        name: String,
+        /// Launch profile from config.toml, the install's default profile when unset
+        #[arg(long)]
+        profile: Option<String>,
+        /// Arguments passed through to blender
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+    /// Install the newest build of every series in the `versions` watch list
+    Update,
+    /// Remove the installs the retention settings don't keep
+    Prune {
+        /// Only list what would be removed
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// Result of a subcommand, printed as text or as a JSON object with `--json`.
+#[derive(Serialize)]
+#[serde(tag = "operation", rename_all = "kebab-case")]
+pub enum Report {
+    ListRemote {
+        versions: Vec<BlenderVersion>,
+    },
+    ListLocal {
+        installs: Vec<LocalBlenderVersion>,
+    },
+    Install {
+        path: PathBuf,
+    },
+    Remove {
+        path: PathBuf,
+    },
+    Launch {
+        path: PathBuf,
+        exit_code: Option<i32>,
+    },
+    Update {
+        updated: Vec<PathBuf>,
+        up_to_date: Vec<PathBuf>,
+    },
+    Prune {
+        #[serde(flatten)]
+        report: PruneReport,
+    },
+}
+
+#[derive(Serialize)]
+struct JsonOutput<'a, T: Serialize> {
+    ok: bool,
+    #[serde(flatten)]
+    body: &'a T,
+}
+
+#[derive(Serialize)]
+struct JsonError<'a> {
+    error: ErrorBody<'a>,
+}
+
+#[derive(Serialize)]
+struct ErrorBody<'a> {
+    message: &'a str,
+}
+
+impl Report {
+    pub fn print(&self, json: bool) {
+        if json {
+            let output = JsonOutput {
+                ok: true,
+                body: self,
+            };
+            println!("{}", serde_json::to_string(&output).unwrap());
+            return;
+        }
+
+        match self {
+            Report::ListRemote { versions } => {
+                for version in versions {
+                    println!(
+                        "{:<10} {:<10} {:<20} {:<12} {:<8} {:<6} {}",
+                        version.version.to_string(),
+                        version.release.to_string(),
+                        version.branch,
+                        version.source,
+                        version.os,
+                        version.arch,
+                        version.link
+                    );
+                }
+            }
+            Report::ListLocal { installs } => {
+                for local in installs {
+                    println!(
+                        "{:<10} {:<10} {:<20} {:<16} {}",
+                        local.blender_version.version.to_string(),
+                        local.blender_version.release.to_string(),
+                        local.blender_version.branch,
+                        local.created,
+                        local.path.display()
+                    );
+                }
+            }
+            // builds for other platforms are only downloaded
+            Report::Install { path } if path.is_file() => {
+                println!("downloaded {}", path.display())
+            }
+            Report::Install { path } => println!("installed {}", path.display()),
+            Report::Remove { path } => println!("removed {}", path.display()),
+            Report::Launch { .. } => {}
+            Report::Update {
+                updated,
+                up_to_date,
+            } => {
+                for path in up_to_date {
+                    println!("up to date {}", path.display());
+                }
+                for path in updated {
+                    println!("updated {}", path.display());
+                }
+            }
+            Report::Prune { report } => {
+                let action = match report.dry_run {
+                    true => "would remove",
+                    false => "removed",
+                };
+                for path in &report.removed {
+                    println!("{action} {}", path.display());
+                }
+                println!("{}", report.summary());
+            }
+        }
+    }
+}
+
+pub fn print_error(err: &str, json: bool) {
+    if json {
+        let output = JsonOutput {
+            ok: false,
+            body: &JsonError {
+                error: ErrorBody { message: err },
+            },
+        };
+        println!("{}", serde_json::to_string(&output).unwrap());
+    } else {
+        eprintln!("error: {err}");
+    }
+}
+
+pub async fn run(command: Command, config: Config) -> Result<Report, String> {
+    let report = match command {
+        Command::ListRemote => Report::ListRemote {
+            versions: remote_versions(&config).await?,
+        },
+        Command::ListLocal => Report::ListLocal {
+            installs: local_versions(&config)?,
+        },
+        Command::Install { version, source } => {
+            let mut versions = remote_versions(&config).await?;
+            if find_remote(&versions, &version).is_none() {
+                // official releases of older series aren't part of the listings
+                let releases = release_versions(&config, &version.series())
+                    .await
+                    .map_err(|err| format!("no remote build found for {version}: {err}"))?;
+                versions.extend(releases);
+            }
+
+            match &source {
+                Some(source) => versions.retain(|remote| remote.source == *source),
+                None => {
+                    // forks reuse Blender's version numbers, they are only installed by source
+                    let forks: Vec<String> = config
+                        .sources()
+                        .iter()
+                        .filter(|source| source.kind == SourceKind::Feed)
+                        .map(|source| source.name().to_owned())
+                        .collect();
+                    versions.retain(|remote| !forks.contains(&remote.source));
+                }
+            }
+
+            let Some(selected) = find_remote(&versions, &version) else {
+                return Err(format!("no remote build found for {version}"));
+            };
+
+            let installed = install(vec![selected.clone()], &config).await?;
+            Report::Install {
+                path: installed.into_iter().next().unwrap_or_default(),
+            }
+        }
+        Command::Remove { name } => {
+            let locals = local_versions(&config)?;
+            let local = find_local(&locals, &name)?;
+
+            if local.protected {
+                return Err(format!("{} is protected", local.path.display()));
+            }
+
+            remove_install(&local.path, |_, _| {})?;
+            update_links(&config)?;
+            update_entries(&config)?;
+            Report::Remove {
+                path: local.path.clone(),
+            }
+        }
+        Command::Launch {
+            name,
+            profile,
+            args,
+        } => {
+            let locals = local_versions(&config)?;
+            let local = find_local(&locals, &name)?;
+
+            let dir_name = local.path.file_name().unwrap_or_default().to_string_lossy();
+            let profile = match profile.as_deref().or(config.default_profile(&dir_name)) {
+                Some(name) => match config.profiles.get(name) {
+                    Some(profile) => Some(profile),
+                    None => return Err(format!("no launch profile named {name}")),
+                },
+                None => None,
+            };
+
+            let status = blender_command(&local.path, profile)
+                .args(args)
+                .status()
+                .await
+                .map_err(|err| err.to_string())?;
+
+            if !status.success() {
+                return Err(format!("blender exited with {status}"));
+            }
+
+            Report::Launch {
+                path: local.path.clone(),
+                exit_code: status.code(),
+            }
+        }
+        Command::Update => {
+            if config.versions.is_empty() {
+                return Err("no versions to watch, set `versions` in config.toml".to_owned());
+            }
+
+            let locals = local_versions(&config)?;
+            let mut versions = remote_versions(&config).await?;
+
+            let untracked: Vec<String> = config
+                .sources()
+                .iter()
+                .filter(|source| !source.kind.is_tracked())
+                .map(|source| source.name().to_owned())
+                .collect();
+            versions.retain(|version| !untracked.contains(&version.source));
+
+            let mut outdated = Vec::new();
+            let mut up_to_date = Vec::new();
+
+            for series in config.versions.iter() {
+                let Some(remote) = latest_in_series(&versions, series) else {
+                    eprintln!("no remote build found for {series}");
+                    continue;
+                };
+
+                let remote_dir = remote.dir_name();
+                if let Some(current) = locals
+                    .iter()
+                    .find(|local| local.path.ends_with(&remote_dir))
+                {
+                    up_to_date.push(current.path.clone());
+                    continue;
+                }
+
+                outdated.push(remote.clone());
+            }
+
+            Report::Update {
+                updated: install(outdated, &config).await?,
+                up_to_date,
+            }
+        }
+        Command::Prune { dry_run } => {
+            if !has_retention(&config) {
+                return Err(
+                    "no retention settings, set `keep_last` or `keep_days` in config.toml"
+                        .to_owned(),
+                );
+            }
+
+            Report::Prune {
+                report: prune(&config, dry_run)?,
+            }
+        }
+    };
+
+    Ok(report)
+}
+
+/// Remote builds for the configured platform and architecture.
+async fn remote_versions(config: &Config) -> Result<Vec<BlenderVersion>, String> {
+    let (os, arch) = (config.platform(), config.arch());
+
+    let listing = get_links(config, &print_retry).await?;
+    for err in &listing.failed {
+        eprintln!("warning: {err}");
+    }
+
+    let mut versions = listing.versions;
+    versions.retain(|version| version.targets(&os, &arch));
+    Ok(versions)
+}
+
+/// Official releases of `series` for the configured platform and architecture, from the
+/// configured release source or download.blender.org.
+async fn release_versions(config: &Config, series: &str) -> Result<Vec<BlenderVersion>, String> {
+    let source = config
+        .sources()
+        .into_iter()
+        .find(|source| source.kind == SourceKind::Release)
+        .unwrap_or_else(|| Source::new(SourceKind::Release));
+
+    let (os, arch) = (config.platform(), config.arch());
+
+    let mut versions = get_series_releases(source.link(), series, &print_retry).await?;
+    versions.retain(|version| version.targets(&os, &arch));
+    for version in versions.iter_mut() {
+        version.source = source.name().to_owned();
+    }
+    Ok(versions)
+}
+
+fn print_retry(retry: &Retry) {
+    eprintln!("{retry}");
+}
+
+fn local_versions(config: &Config) -> Result<Vec<LocalBlenderVersion>, String> {
+    let file_list = check_downloaded(config)?;
+    Ok(parse_downloaded(file_list))
+}
+
+/// Picks the most stable remote build matching `version`, preferring the host's and official
+/// releases over daily builds.
+fn find_remote<'a>(
+    versions: &'a [BlenderVersion],
+    version: &Version,
+) -> Option<&'a BlenderVersion> {
+    versions
+        .iter()
+        .filter(|remote| remote.version == *version)
+        .max_by_key(|remote| (remote.release, remote.is_host(), remote.hash.is_empty()))
+}
+
+/// Finds an install by its directory name, or by version if only one matches.
+fn find_local<'a>(
+    locals: &'a [LocalBlenderVersion],
+    name: &str,
+) -> Result<&'a LocalBlenderVersion, String> {
+    if let Some(local) = locals.iter().find(|local| local.path.ends_with(name)) {
+        return Ok(local);
+    }
+
+    let matches: Vec<&LocalBlenderVersion> = locals
+        .iter()
+        .filter(|local| local.blender_version.version.to_string() == name)
+        .collect();
+
+    match matches.as_slice() {
+        [] => Err(format!("no local install found for {name}")),
+        [local] => Ok(local),
+        _ => {
+            let names: Vec<String> = matches
+                .iter()
+                .map(|local| local.path.display().to_string())
+                .collect();
+            Err(format!(
+                "{name} is ambiguous, use one of:\n{}",
+                names.join("\n")
+            ))
+        }
+    }
+}
+
+/// Installs `versions` through the download manager and waits for every job to finish.
+///
+/// SIGINT and SIGTERM cancel the downloads, SIGUSR1 pauses and SIGUSR2 resumes them.
+async fn install(versions: Vec<BlenderVersion>, config: &Config) -> Result<Vec<PathBuf>, String> {
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<Message>(16);
+    let mut manager = DownloadManager::new(config.clone(), Arc::new(tx));
+
+    // the same build can be picked for several watched series, it is installed once
+    let mut pending = 0;
+    for version in versions {
+        if manager.enqueue(version).is_ok() {
+            pending += 1;
+        }
+    }
+
+    let listen = |kind| signal(kind).map_err(|err| err.to_string());
+    let mut interrupt = listen(SignalKind::interrupt())?;
+    let mut terminate = listen(SignalKind::terminate())?;
+    let mut pause = listen(SignalKind::user_defined1())?;
+    let mut resume = listen(SignalKind::user_defined2())?;
+
+    let mut names = HashMap::new();
+    let mut installed = Vec::new();
+    let mut errors = Vec::new();
+    let mut cancelled = false;
+
+    while pending > 0 {
+        let message = tokio::select! {
+            message = rx.recv() => message,
+            _ = interrupt.recv() => {
+                cancelled = true;
+                manager.cancel_all();
+                continue;
+            }
+            _ = terminate.recv() => {
+                cancelled = true;
+                manager.cancel_all();
+                continue;
+            }
+            _ = pause.recv() => {
+                manager.pause_all();
+                continue;
+            }
+            _ = resume.recv() => {
+                manager.resume_all();
+                continue;
+            }
+        };
+
+        let Some(Message::Job(id, event)) = message else {
+            continue;
+        };
+
+        match event {
+            JobEvent::Queued(version) => {
+                names.insert(id, version.dir_name());
+            }
+            JobEvent::Active => eprintln!("[{id}] downloading {}", names[&id]),
+            JobEvent::Paused => eprintln!("[{id}] paused"),
+            JobEvent::Progress(progress) => eprintln!("[{id}] {progress}"),
+            JobEvent::Extracting(progress) => eprintln!(
+                "[{id}] extracting {:>5.1}% {} entries",
+                progress.ratio() * 100.0,
+                progress.entries
+            ),
+            JobEvent::Status(s) => eprintln!("[{id}] {s}"),
+            JobEvent::Retry(retry) => eprintln!("[{id}] {retry}"),
+            JobEvent::Done(path) => {
+                installed.push(path);
+                pending -= 1;
+            }
+            JobEvent::Failed(err) => {
+                errors.push(format!("{}: {err}", names[&id]));
+                pending -= 1;
+            }
+            JobEvent::Cancelled => {
+                eprintln!("[{id}] cancelled");
+                pending -= 1;
+            }
+        }
+    }
+
+    if cancelled {
+        errors.push("cancelled".to_owned());
+    }
+
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+
+    Ok(installed)
+}
@@ -1,30 +1,128 @@
-use std::{io::Read, path::PathBuf, str::FromStr};
+use std::{collections::BTreeMap, io::Read, path::PathBuf, str::FromStr};
 
 use serde::Deserialize;
 
+use crate::{
+    blender_utils::{host_arch, host_os, normalize_arch, normalize_os},
+    desktop::DesktopEntries,
+    source::{Source, SourceKind},
+};
+
 #[derive(Debug, Deserialize, Default, Clone)]
 pub struct Config {
+    /// Watched series, e.g. "4.2" or "4.3.0", kept up to date by `update`.
+    #[serde(default)]
     pub versions: Vec<String>,
     pub path: String,
     pub archive: Option<bool>,
+    /// How many downloads run at the same time, 2 when unset.
+    pub max_downloads: Option<usize>,
+    /// Keep the `.part` file of a cancelled download so it can be resumed later.
+    pub keep_partial: Option<bool>,
+    /// Unpack `.tar.xz` builds while they download instead of saving the archive first.
+    pub stream: Option<bool>,
     #[serde(default)]
     pub link: String,
+    /// Listings to merge, the single `link` or `archive` listing when empty.
+    #[serde(default)]
+    pub sources: Vec<Source>,
+    /// Platform builds are listed for, "linux", "windows", "macos" or "all", the host's when unset.
+    pub platform: Option<String>,
+    /// Architecture builds are listed for, "x64", "arm64" or "all", the host's when unset.
+    pub arch: Option<String>,
+    /// Also link `blender-current` and the series links from `~/.local/bin`.
+    pub bin_links: Option<bool>,
+    /// Write `.desktop` launchers for "all" installs or only the "defaults" links.
+    pub desktop_entries: Option<DesktopEntries>,
+    /// `prune` keeps this many of the newest builds of each series and branch.
+    pub keep_last: Option<usize>,
+    /// `prune` keeps builds installed in the last this many days.
+    pub keep_days: Option<u64>,
+    /// Install directories `prune` never removes.
+    #[serde(default)]
+    pub keep: Vec<String>,
+    /// Named sets of arguments and environment to launch builds with.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, LaunchProfile>,
+    /// Profile used by default for an install, keyed by directory name.
+    #[serde(default)]
+    pub default_profiles: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct LaunchProfile {
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Directory blender starts in, the current one when unset.
+    pub working_dir: Option<PathBuf>,
+}
+
+impl Config {
+    /// Platform the listings are filtered to.
+    pub fn platform(&self) -> String {
+        match &self.platform {
+            Some(platform) => normalize_os(platform),
+            None => host_os(),
+        }
+    }
+
+    /// Architecture the listings are filtered to.
+    pub fn arch(&self) -> String {
+        match &self.arch {
+            Some(arch) => normalize_arch(arch),
+            None => host_arch(),
+        }
+    }
+
+    /// Sources listings are fetched from.
+    pub fn sources(&self) -> Vec<Source> {
+        if !self.sources.is_empty() {
+            return self.sources.clone();
+        }
+
+        let kind = match self.archive {
+            Some(true) => SourceKind::Archive,
+            _ => SourceKind::Daily,
+        };
+
+        let mut source = Source::new(kind);
+        if !self.link.is_empty() {
+            source.link = Some(self.link.clone());
+        }
+        vec![source]
+    }
+
+    /// Name of the default profile of the install in `dir_name`, if it exists.
+    pub fn default_profile(&self, dir_name: &str) -> Option<&str> {
+        self.default_profiles
+            .get(dir_name)
+            .filter(|name| self.profiles.contains_key(*name))
+            .map(String::as_str)
+    }
 }
 
 pub fn parse_config() -> Result<Config, String> {
     let path = PathBuf::from_str("config.toml").map_err(|err|err.to_string())?;
     if !path.exists() {
-        println!("config.toml not found");
+        eprintln!("config.toml not found");
         return Ok(Config::default());
     }
 
     let mut file = std::fs::File::open(path).map_err(|err|err.to_string())?;
     let mut buf = Vec::with_capacity(100_000);
-    let _ = file.read_to_end(&mut buf).expect("could not read file");
+    file.read_to_end(&mut buf)
+        .map_err(|err| format!("could not read config.toml: {err}"))?;
 
     let contents = String::from_utf8(buf).map_err(|err| err.to_string())?;
 
-    let mut config: Config = toml::from_str(&contents).unwrap();
+    let mut config: Config =
+        toml::from_str(&contents).map_err(|err| format!("invalid config.toml: {err}"))?;
+
+    if !config.link.is_empty() {
+        return Ok(config);
+    }
 
     config.link = "https://builder.blender.org/download/daily/".to_owned();
 
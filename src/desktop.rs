@@ -0,0 +1,342 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::{blender_utils::executable, config::Config, manifest};
+
+/// Prefix of every desktop entry and icon the manager writes.
+const PREFIX: &str = "blender-download-manager-";
+
+/// Which installs get a `.desktop` launcher.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DesktopEntries {
+    /// One per install.
+    All,
+    /// One per `blender-current` and `blender-X.Y` link.
+    Defaults,
+}
+
+struct Entry {
+    id: String,
+    name: String,
+    exec: PathBuf,
+    /// Install directory holding the bundled icon.
+    install: PathBuf,
+}
+
+/// `$XDG_DATA_HOME`, or `~/.local/share` when unset.
+fn data_home() -> Result<PathBuf, String> {
+    if let Some(data_home) = std::env::var_os("XDG_DATA_HOME").filter(|dir| !dir.is_empty()) {
+        return Ok(PathBuf::from(data_home));
+    }
+
+    let home = std::env::var("HOME").map_err(|_| "HOME is not set".to_owned())?;
+    Ok(PathBuf::from(home).join(".local/share"))
+}
+
+/// Writes a desktop entry and icon under `$XDG_DATA_HOME` for the installs selected by
+/// `Config::desktop_entries` and removes the ones of installs that are gone. Does nothing
+/// when it is unset.
+pub fn update_entries(config: &Config) -> Result<(), String> {
+    let Some(mode) = config.desktop_entries else {
+        return Ok(());
+    };
+
+    write_entries(Path::new(&config.path), mode, &data_home()?)
+}
+
+/// Writes the entries `mode` selects for the installs in `path` under `data_home`.
+fn write_entries(path: &Path, mode: DesktopEntries, data_home: &Path) -> Result<(), String> {
+    let base = std::fs::canonicalize(path).map_err(|err| err.to_string())?;
+    let entries = match mode {
+        DesktopEntries::All => install_entries(&base)?,
+        DesktopEntries::Defaults => link_entries(&base)?,
+    };
+
+    let applications = data_home.join("applications");
+    let icons = data_home.join("icons/hicolor/scalable/apps");
+    std::fs::create_dir_all(&applications).map_err(|err| err.to_string())?;
+    std::fs::create_dir_all(&icons).map_err(|err| err.to_string())?;
+
+    for entry in &entries {
+        let icon = entry.install.join("blender.svg");
+        let has_icon = std::fs::copy(&icon, icons.join(format!("{}.svg", entry.id))).is_ok();
+
+        let desktop = format!(
+            "[Desktop Entry]\n\
+             Type=Application\n\
+             Name={}\n\
+             Comment=3D modeling, animation, rendering and post-production\n\
+             Exec={} %f\n\
+             Icon={}\n\
+             Terminal=false\n\
+             Categories=Graphics;3DGraphics;\n\
+             MimeType=application/x-blender;\n",
+            entry.name,
+            exec_arg(&entry.exec),
+            if has_icon { &entry.id } else { "blender" },
+        );
+
+        std::fs::write(applications.join(format!("{}.desktop", entry.id)), desktop)
+            .map_err(|err| err.to_string())?;
+    }
+
+    let wanted: Vec<&str> = entries.iter().map(|entry| entry.id.as_str()).collect();
+    remove_stale(&applications, "desktop", &wanted)?;
+    remove_stale(&icons, "svg", &wanted)
+}
+
+/// Quotes `path` as an `Exec` argument, escaping it as the Desktop Entry spec requires.
+fn exec_arg(path: &Path) -> String {
+    let mut quoted = String::new();
+    for c in path.to_string_lossy().chars() {
+        match c {
+            '"' | '`' | '$' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '%' => quoted.push_str("%%"),
+            _ => quoted.push(c),
+        }
+    }
+
+    // the value is a string, whose escapes are undone before the quoting
+    let escaped = quoted
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+        .replace('\r', "\\r");
+    format!("\"{escaped}\"")
+}
+
+fn install_entries(base: &Path) -> Result<Vec<Entry>, String> {
+    let installs = manifest::sync(base)?;
+
+    Ok(installs
+        .into_iter()
+        .map(|(path, manifest)| {
+            let dir_name = path.file_name().unwrap_or_default().to_string_lossy();
+
+            let mut name = format!("Blender {} {}", manifest.version, manifest.release);
+            if !manifest.branch.is_empty() {
+                name.push_str(&format!(" ({})", manifest.branch));
+            }
+
+            Entry {
+                id: format!("{PREFIX}{dir_name}"),
+                name,
+                exec: executable(&path),
+                install: path,
+            }
+        })
+        .collect())
+}
+
+fn link_entries(base: &Path) -> Result<Vec<Entry>, String> {
+    let mut entries = Vec::new();
+
+    for entry in std::fs::read_dir(base).map_err(|err| err.to_string())? {
+        let entry = entry.map_err(|err| err.to_string())?;
+        let name = entry.file_name().to_string_lossy().to_string();
+
+        if !entry
+            .file_type()
+            .map(|kind| kind.is_symlink())
+            .unwrap_or(false)
+        {
+            continue;
+        }
+        let Some(series) = name.strip_prefix("blender-") else {
+            continue;
+        };
+        let Ok(install) = std::fs::canonicalize(entry.path()) else {
+            continue;
+        };
+
+        entries.push(Entry {
+            id: format!("{PREFIX}{name}"),
+            name: format!("Blender {series}"),
+            exec: entry.path().join("blender"),
+            install,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Removes files with `extension` the manager wrote in `dir` whose id isn't in `wanted`.
+fn remove_stale(dir: &Path, extension: &str, wanted: &[&str]) -> Result<(), String> {
+    for entry in std::fs::read_dir(dir).map_err(|err| err.to_string())? {
+        let entry = entry.map_err(|err| err.to_string())?;
+        let path = entry.path();
+
+        let Some(id) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(&format!(".{extension}")))
+        else {
+            continue;
+        };
+
+        if id.starts_with(PREFIX) && !wanted.contains(&id) {
+            let _ = std::fs::remove_file(&path);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::links::update_links;
+
+    const STABLE: &str = "blender-4.2.3-linux-x64";
+    const ALPHA: &str = "blender-4.3.0-alpha+main.2f6cf1a2eac8-linux.x86_64-release";
+
+    fn install(base: &Path, dir_name: &str, icon: bool) {
+        let dir = base.join(dir_name);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("blender"), "").unwrap();
+        if icon {
+            fs::write(dir.join("blender.svg"), "<svg/>").unwrap();
+        }
+    }
+
+    fn ids(dir: &Path, extension: &str) -> Vec<String> {
+        let mut ids: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                name.strip_suffix(&format!(".{extension}"))
+                    .map(str::to_owned)
+            })
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn all_writes_an_entry_per_install() {
+        let base = tempfile::tempdir().unwrap();
+        let data_home = tempfile::tempdir().unwrap();
+        install(base.path(), STABLE, true);
+        install(base.path(), ALPHA, false);
+
+        write_entries(base.path(), DesktopEntries::All, data_home.path()).unwrap();
+
+        let applications = data_home.path().join("applications");
+        assert_eq!(
+            ids(&applications, "desktop"),
+            [format!("{PREFIX}{STABLE}"), format!("{PREFIX}{ALPHA}")]
+        );
+
+        let stable =
+            fs::read_to_string(applications.join(format!("{PREFIX}{STABLE}.desktop"))).unwrap();
+        let exec = base
+            .path()
+            .canonicalize()
+            .unwrap()
+            .join(STABLE)
+            .join("blender");
+        assert!(stable.contains("Name=Blender 4.2.3 stable\n"));
+        assert!(stable.contains(&format!("Exec=\"{}\" %f\n", exec.display())));
+        assert!(stable.contains(&format!("Icon={PREFIX}{STABLE}\n")));
+
+        // builds without a bundled icon use the theme's
+        let alpha =
+            fs::read_to_string(applications.join(format!("{PREFIX}{ALPHA}.desktop"))).unwrap();
+        assert!(alpha.contains("Icon=blender\n"));
+
+        let icons = data_home.path().join("icons/hicolor/scalable/apps");
+        assert_eq!(ids(&icons, "svg"), [format!("{PREFIX}{STABLE}")]);
+    }
+
+    #[test]
+    fn exec_escapes_the_install_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_home = tempfile::tempdir().unwrap();
+        let base = dir.path().join("my \"blender$");
+        install(&base, STABLE, false);
+
+        write_entries(&base, DesktopEntries::All, data_home.path()).unwrap();
+
+        let desktop = fs::read_to_string(
+            data_home
+                .path()
+                .join(format!("applications/{PREFIX}{STABLE}.desktop")),
+        )
+        .unwrap();
+        let dir = dir.path().canonicalize().unwrap();
+        assert!(desktop.contains(&format!(
+            "Exec=\"{}/my \\\\\"blender\\\\$/{STABLE}/blender\" %f\n",
+            dir.display()
+        )));
+    }
+
+    #[test]
+    fn defaults_writes_an_entry_per_link() {
+        let base = tempfile::tempdir().unwrap();
+        let data_home = tempfile::tempdir().unwrap();
+        install(base.path(), STABLE, true);
+        install(base.path(), ALPHA, true);
+
+        let config = Config {
+            path: base.path().to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        update_links(&config).unwrap();
+
+        write_entries(base.path(), DesktopEntries::Defaults, data_home.path()).unwrap();
+
+        let applications = data_home.path().join("applications");
+        assert_eq!(
+            ids(&applications, "desktop"),
+            [
+                format!("{PREFIX}blender-4.2"),
+                format!("{PREFIX}blender-4.3"),
+                format!("{PREFIX}blender-current"),
+            ]
+        );
+
+        let current =
+            fs::read_to_string(applications.join(format!("{PREFIX}blender-current.desktop")))
+                .unwrap();
+        assert!(current.contains("Name=Blender current\n"));
+
+        let icons = data_home.path().join("icons/hicolor/scalable/apps");
+        assert_eq!(ids(&icons, "svg").len(), 3);
+    }
+
+    #[test]
+    fn entries_of_removed_installs_are_deleted() {
+        let base = tempfile::tempdir().unwrap();
+        let data_home = tempfile::tempdir().unwrap();
+        install(base.path(), STABLE, true);
+        install(base.path(), ALPHA, true);
+
+        write_entries(base.path(), DesktopEntries::All, data_home.path()).unwrap();
+
+        // entries the manager didn't write are left alone
+        let applications = data_home.path().join("applications");
+        fs::write(applications.join("org.blender.Blender.desktop"), "").unwrap();
+
+        fs::remove_dir_all(base.path().join(ALPHA)).unwrap();
+        write_entries(base.path(), DesktopEntries::All, data_home.path()).unwrap();
+
+        assert_eq!(
+            ids(&applications, "desktop"),
+            [
+                format!("{PREFIX}{STABLE}"),
+                "org.blender.Blender".to_owned()
+            ]
+        );
+
+        let icons = data_home.path().join("icons/hicolor/scalable/apps");
+        assert_eq!(ids(&icons, "svg"), [format!("{PREFIX}{STABLE}")]);
+    }
+}
@@ -0,0 +1,551 @@
+use std::{
+    cell::Cell,
+    fmt::Display,
+    fs::{self, File},
+    io::{self, BufReader, Read},
+    path::{Component, Path, PathBuf},
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use tar::EntryType;
+use xz2::read::XzDecoder;
+
+use crate::blender_utils;
+
+#[derive(Debug)]
+pub enum ExtractError {
+    Io(io::Error),
+    Zip(zip::result::ZipError),
+    /// An entry would be written outside of the target directory.
+    UnsafePath(PathBuf),
+    UnsupportedFormat(PathBuf),
+    /// The extracted build is missing its executable or data directory.
+    Incomplete(String),
+    Cancelled,
+}
+
+impl Display for ExtractError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExtractError::Io(err) => write!(f, "extraction failed: {err}"),
+            ExtractError::Zip(err) => write!(f, "extraction failed: {err}"),
+            ExtractError::UnsafePath(path) => {
+                write!(f, "archive entry escapes the target: {}", path.display())
+            }
+            ExtractError::UnsupportedFormat(path) => {
+                write!(f, "unsupported archive format: {}", path.display())
+            }
+            ExtractError::Incomplete(missing) => write!(f, "incomplete build: missing {missing}"),
+            ExtractError::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl From<io::Error> for ExtractError {
+    fn from(err: io::Error) -> Self {
+        ExtractError::Io(err)
+    }
+}
+
+impl From<zip::result::ZipError> for ExtractError {
+    fn from(err: zip::result::ZipError) -> Self {
+        ExtractError::Zip(err)
+    }
+}
+
+/// How far an extraction got, `read` of `total` archive bytes have been consumed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExtractProgress {
+    pub entries: usize,
+    pub bytes: u64,
+    pub read: u64,
+    pub total: u64,
+}
+
+impl ExtractProgress {
+    pub fn ratio(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        (self.read as f64 / self.total as f64).clamp(0.0, 1.0)
+    }
+}
+
+const REPORT_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Counts the compressed bytes read so progress can be measured against the archive size.
+struct CountingReader<R> {
+    inner: R,
+    count: Rc<Cell<u64>>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count.set(self.count.get() + n as u64);
+        Ok(n)
+    }
+}
+
+/// Makes `path` relative, rejecting absolute paths and `..` components.
+fn safe_path(path: &Path) -> Result<PathBuf, ExtractError> {
+    let mut result = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => result.push(part),
+            Component::CurDir => {}
+            _ => return Err(ExtractError::UnsafePath(path.to_owned())),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Rejects a link whose target, resolved from `base`, points outside of the target directory.
+fn check_link(base: &Path, link: &Path) -> Result<(), ExtractError> {
+    let mut depth = base.components().count() as isize;
+
+    for component in link.components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir if depth > 0 => depth -= 1,
+            _ => return Err(ExtractError::UnsafePath(link.to_owned())),
+        }
+    }
+
+    Ok(())
+}
+
+/// Rejects `path` unless the deepest part of it that exists resolves inside `root`, which
+/// must be canonical. Catches entries that would be written through links extracted earlier.
+fn check_inside(root: &Path, path: &Path) -> Result<(), ExtractError> {
+    let mut existing = path;
+    while existing.symlink_metadata().is_err() {
+        match existing.parent() {
+            Some(parent) => existing = parent,
+            None => break,
+        }
+    }
+
+    if !existing.canonicalize()?.starts_with(root) {
+        return Err(ExtractError::UnsafePath(path.to_owned()));
+    }
+
+    Ok(())
+}
+
+/// Hidden directory in `base` a build is extracted into before it is moved into place.
+pub fn staging_dir(base: &Path, dir_name: &str) -> PathBuf {
+    base.join(format!(".staging-{dir_name}"))
+}
+
+/// File in a staging directory holding the PID of the process installing into it.
+const OWNER_FILE: &str = ".owner";
+
+/// Creates the staging directory of `dir_name` for this process, after recovering what an
+/// install that never finished left there. Fails when another running process owns it.
+pub fn create_staging(base: &Path, dir_name: &str) -> Result<PathBuf, String> {
+    let staging = staging_dir(base, dir_name);
+
+    if let Some(pid) = owner(&staging).filter(|pid| *pid != std::process::id()) {
+        return Err(format!("{dir_name} is being installed by process {pid}"));
+    }
+    recover_staging(base, &staging);
+
+    fs::create_dir_all(&staging)
+        .and_then(|()| fs::write(staging.join(OWNER_FILE), std::process::id().to_string()))
+        .map_err(|err| format!("could not create {}: {err}", staging.display()))?;
+    Ok(staging)
+}
+
+/// PID of the running process that owns `staging`, if any.
+fn owner(staging: &Path) -> Option<u32> {
+    let pid = fs::read_to_string(staging.join(OWNER_FILE)).ok()?;
+    let pid = pid.trim().parse().ok()?;
+    is_running(pid).then_some(pid)
+}
+
+fn is_running(pid: u32) -> bool {
+    let proc = Path::new("/proc");
+    if proc.is_dir() {
+        return proc.join(pid.to_string()).exists();
+    }
+
+    // without procfs, ask kill whether the process can be signalled
+    std::process::Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(true)
+}
+
+/// Moves a previous install that a crashed install had set aside back into place, then
+/// removes `staging`. Nothing is removed when the previous install can't be put back.
+fn recover_staging(base: &Path, staging: &Path) {
+    let previous = staging.join(".previous");
+
+    if previous.is_dir() {
+        let dir_name = staging
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix(".staging-"));
+
+        if let Some(target) = dir_name.map(|dir_name| base.join(dir_name)) {
+            if !target.exists() && fs::rename(&previous, &target).is_err() {
+                return;
+            }
+        }
+    }
+
+    let _ = fs::remove_dir_all(staging);
+}
+
+/// Checks the build extracted into `staging` and renames it to `base/dir_name`. A previous
+/// install of the same build is only replaced once the new one is complete.
+///
+/// Forks ship the data directory of the Blender series they are based on, for them `series`
+/// is `None` and any data directory will do.
+pub fn install_staged(
+    staging: &Path,
+    base: &Path,
+    dir_name: &str,
+    series: Option<&str>,
+) -> Result<PathBuf, ExtractError> {
+    let build = match staging.join(dir_name) {
+        build if build.is_dir() => build,
+        // archives of forks don't always unpack into a directory named like the archive
+        build => single_dir(staging).unwrap_or(build),
+    };
+
+    if blender_utils::find_executable(&build, dir_name).is_none() {
+        return Err(ExtractError::Incomplete("blender executable".to_owned()));
+    }
+
+    let has_data = match series {
+        Some(series) => build.join(series).is_dir(),
+        None => fs::read_dir(&build)?.flatten().any(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            entry.path().is_dir() && name.split('.').all(|part| part.parse::<u32>().is_ok())
+        }),
+    };
+    if !has_data {
+        let series = series.unwrap_or("version");
+        return Err(ExtractError::Incomplete(format!("{series} data directory")));
+    }
+
+    let target = base.join(dir_name);
+    let previous = staging.join(".previous");
+
+    if target.exists() {
+        fs::rename(&target, &previous)?;
+    }
+
+    if let Err(err) = fs::rename(&build, &target) {
+        let _ = fs::rename(&previous, &target);
+        return Err(err.into());
+    }
+
+    let _ = fs::remove_dir_all(staging);
+    Ok(target)
+}
+
+/// The only directory in `path`, ignoring the backup of a previous install.
+fn single_dir(path: &Path) -> Option<PathBuf> {
+    let mut dirs = fs::read_dir(path)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.file_name() != ".previous" && entry.path().is_dir());
+
+    match (dirs.next(), dirs.next()) {
+        (Some(dir), None) => Some(dir.path()),
+        _ => None,
+    }
+}
+
+/// Removes staging directories left behind by installs that never finished, restoring the
+/// installs they were replacing. Directories of installs still running are left alone.
+pub fn clean_staging(base: &Path) {
+    let Ok(entries) = fs::read_dir(base) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let staging = entry.path();
+        if entry.file_name().to_string_lossy().starts_with(".staging-") && owner(&staging).is_none()
+        {
+            recover_staging(base, &staging);
+        }
+    }
+}
+
+/// Extracts a `.tar.xz` or `.zip` archive into `target`.
+///
+/// `cancelled` is checked before every entry and `report` is called a few times a second.
+pub fn extract(
+    archive: &Path,
+    target: &Path,
+    cancelled: impl Fn() -> bool,
+    report: impl FnMut(ExtractProgress),
+) -> Result<(), ExtractError> {
+    let name = archive.to_string_lossy();
+
+    if name.ends_with(".tar.xz") {
+        extract_tar_xz(archive, target, cancelled, report)
+    } else if name.ends_with(".zip") {
+        extract_zip(archive, target, cancelled, report)
+    } else {
+        Err(ExtractError::UnsupportedFormat(archive.to_owned()))
+    }
+}
+
+fn extract_tar_xz(
+    archive: &Path,
+    target: &Path,
+    cancelled: impl Fn() -> bool,
+    report: impl FnMut(ExtractProgress),
+) -> Result<(), ExtractError> {
+    let file = File::open(archive)?;
+    let total = file.metadata()?.len();
+
+    unpack_tar_xz(BufReader::new(file), total, target, cancelled, report)
+}
+
+/// Unpacks a `.tar.xz` stream into `target` as it is read, e.g. straight from a download.
+pub fn extract_stream(
+    reader: impl Read,
+    target: &Path,
+    cancelled: impl Fn() -> bool,
+) -> Result<(), ExtractError> {
+    unpack_tar_xz(reader, 0, target, cancelled, |_| {})
+}
+
+fn unpack_tar_xz(
+    reader: impl Read,
+    total: u64,
+    target: &Path,
+    cancelled: impl Fn() -> bool,
+    mut report: impl FnMut(ExtractProgress),
+) -> Result<(), ExtractError> {
+    let mut progress = ExtractProgress {
+        total,
+        ..Default::default()
+    };
+
+    let count = Rc::new(Cell::new(0));
+    let reader = CountingReader {
+        inner: reader,
+        count: count.clone(),
+    };
+
+    let mut tar = tar::Archive::new(XzDecoder::new(reader));
+    tar.set_preserve_permissions(true);
+    tar.set_overwrite(true);
+
+    let mut timer = Instant::now();
+
+    for entry in tar.entries()? {
+        if cancelled() {
+            return Err(ExtractError::Cancelled);
+        }
+
+        let mut entry = entry?;
+        let path = safe_path(&entry.path()?)?;
+
+        match entry.header().entry_type() {
+            EntryType::Symlink => {
+                let link = entry.link_name()?.unwrap_or_default();
+                check_link(path.parent().unwrap_or(Path::new("")), &link)?;
+            }
+            EntryType::Link => {
+                let link = entry.link_name()?.unwrap_or_default();
+                check_link(Path::new(""), &link)?;
+            }
+            _ => {}
+        }
+
+        if !entry.unpack_in(target)? {
+            return Err(ExtractError::UnsafePath(path));
+        }
+
+        progress.entries += 1;
+        progress.bytes += entry.size();
+        progress.read = count.get();
+
+        if timer.elapsed() > REPORT_INTERVAL {
+            report(progress);
+            timer = Instant::now();
+        }
+    }
+
+    progress.read = progress.total;
+    report(progress);
+
+    Ok(())
+}
+
+fn extract_zip(
+    archive: &Path,
+    target: &Path,
+    cancelled: impl Fn() -> bool,
+    mut report: impl FnMut(ExtractProgress),
+) -> Result<(), ExtractError> {
+    let file = File::open(archive)?;
+
+    let mut progress = ExtractProgress {
+        total: file.metadata()?.len(),
+        ..Default::default()
+    };
+
+    let mut zip = zip::ZipArchive::new(BufReader::new(file))?;
+    let mut timer = Instant::now();
+
+    fs::create_dir_all(target)?;
+    let root = target.canonicalize()?;
+
+    for idx in 0..zip.len() {
+        if cancelled() {
+            return Err(ExtractError::Cancelled);
+        }
+
+        let mut entry = zip.by_index(idx)?;
+        let path = safe_path(Path::new(entry.name()))?;
+        let out = target.join(&path);
+
+        if entry.is_dir() {
+            check_inside(&root, &out)?;
+            fs::create_dir_all(&out)?;
+        } else {
+            if let Some(parent) = out.parent() {
+                check_inside(&root, parent)?;
+                fs::create_dir_all(parent)?;
+            }
+
+            if entry.is_symlink() {
+                let mut link = String::new();
+                entry.read_to_string(&mut link)?;
+                check_link(path.parent().unwrap_or(Path::new("")), Path::new(&link))?;
+
+                let _ = fs::remove_file(&out);
+                std::os::unix::fs::symlink(&link, &out)?;
+            } else {
+                // never write through a link an earlier entry put here
+                if out.symlink_metadata().is_ok_and(|meta| meta.is_symlink()) {
+                    fs::remove_file(&out)?;
+                }
+                io::copy(&mut entry, &mut File::create(&out)?)?;
+
+                if let Some(mode) = entry.unix_mode() {
+                    use std::os::unix::fs::PermissionsExt;
+                    fs::set_permissions(&out, fs::Permissions::from_mode(mode))?;
+                }
+            }
+        }
+
+        progress.entries += 1;
+        progress.bytes += entry.size();
+        progress.read += entry.compressed_size();
+
+        if timer.elapsed() > REPORT_INTERVAL {
+            report(progress);
+            timer = Instant::now();
+        }
+    }
+
+    progress.read = progress.total;
+    report(progress);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use zip::{write::SimpleFileOptions, ZipWriter};
+
+    use super::*;
+
+    fn write_zip(path: &Path, build: impl FnOnce(&mut ZipWriter<File>)) {
+        let mut zip = ZipWriter::new(File::create(path).unwrap());
+        build(&mut zip);
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn zip_extracts_files_and_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("build.zip");
+        let options = SimpleFileOptions::default();
+
+        write_zip(&archive, |zip| {
+            zip.add_directory("build/lib/", options).unwrap();
+            zip.start_file("build/lib/libfoo.so.1", options).unwrap();
+            zip.write_all(b"lib").unwrap();
+            zip.add_symlink("build/lib/libfoo.so", "libfoo.so.1", options)
+                .unwrap();
+        });
+
+        let target = dir.path().join("out");
+        extract(&archive, &target, || false, |_| {}).unwrap();
+
+        let link = target.join("build/lib/libfoo.so");
+        assert_eq!(fs::read_link(&link).unwrap(), Path::new("libfoo.so.1"));
+        assert_eq!(fs::read(&link).unwrap(), b"lib");
+    }
+
+    #[test]
+    fn zip_rejects_entries_written_through_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("evil.zip");
+        let options = SimpleFileOptions::default();
+
+        // each link only climbs one level, but d/b/c resolves to the parent of the target
+        write_zip(&archive, |zip| {
+            zip.add_directory("d/", options).unwrap();
+            zip.add_symlink("d/b", "..", options).unwrap();
+            zip.add_symlink("d/b/c", "..", options).unwrap();
+            zip.start_file("d/b/c/x", options).unwrap();
+            zip.write_all(b"escaped").unwrap();
+        });
+
+        let target = dir.path().join("out");
+        let result = extract(&archive, &target, || false, |_| {});
+
+        assert!(matches!(result, Err(ExtractError::UnsafePath(_))));
+        assert!(!dir.path().join("x").exists());
+    }
+
+    #[test]
+    fn clean_staging_restores_the_previous_install() {
+        let base = tempfile::tempdir().unwrap();
+        let staging = staging_dir(base.path(), "blender-4.2.3-linux-x64");
+
+        // crashed between moving the old install aside and moving the new one in
+        fs::create_dir_all(staging.join(".previous/4.2")).unwrap();
+        fs::create_dir_all(staging.join("blender-4.2.3-linux-x64")).unwrap();
+
+        clean_staging(base.path());
+
+        assert!(base.path().join("blender-4.2.3-linux-x64/4.2").is_dir());
+        assert!(!staging.exists());
+    }
+
+    #[test]
+    fn clean_staging_keeps_running_installs() {
+        let base = tempfile::tempdir().unwrap();
+
+        let running = create_staging(base.path(), "blender-4.2.3-linux-x64").unwrap();
+        let stale = staging_dir(base.path(), "blender-4.1.1-linux-x64");
+        fs::create_dir_all(&stale).unwrap();
+        fs::write(stale.join(OWNER_FILE), u32::MAX.to_string()).unwrap();
+
+        clean_staging(base.path());
+
+        assert!(running.is_dir());
+        assert!(!stale.exists());
+    }
+}
@@ -1,25 +1,131 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::RandomState;
+use std::collections::HashSet;
+use std::fmt::Display;
 use std::fs::File;
-use std::io::Write;
-use std::path::PathBuf;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
 
-use reqwest::{header::HeaderName, Request, Url};
+use futures::future::join_all;
+use regex::Regex;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, RANGE},
+    Client, RequestBuilder, StatusCode, Url,
+};
 use serde_json::json;
+use sha2::{Digest, Sha256};
+use tokio::sync::{mpsc, watch::Receiver};
 
+use crate::extract::extract_stream;
+use crate::manager::JobControl;
+use crate::source::{Source, SourceKind};
 use crate::tracker::ProgressTracker;
-use crate::tui::TxMessage;
-use crate::{blender_utils, config::Config, tui::Message, BlenderVersion};
+use crate::tui::{JobEvent, JobId, TxMessage};
+use crate::{
+    blender_utils::{self, BlenderMatcher},
+    config::Config,
+    tui::Message,
+    BlenderVersion,
+};
 
 // use crate::tracker::ProgressTracker;
 
-struct Getter {
-    request: Request,
+/// Time allowed to open a connection.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// A response that sends nothing for this long has stalled.
+const STALL_TIMEOUT: Duration = Duration::from_secs(30);
+/// Time allowed for a whole listing or checksum request.
+const TEXT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Tries a request gets before its error is returned.
+const MAX_ATTEMPTS: u32 = 5;
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// A failed request that is tried again after `delay`.
+#[derive(Debug, Clone)]
+pub struct Retry {
+    pub attempt: u32,
+    pub delay: Duration,
+    pub error: String,
+}
+
+impl Display for Retry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "attempt {}/{MAX_ATTEMPTS} failed: {}, retrying in {:.1}s",
+            self.attempt,
+            self.error,
+            self.delay.as_secs_f32()
+        )
+    }
+}
+
+/// Called with every retry of a request, to report it.
+pub type OnRetry<'a> = &'a (dyn Fn(&Retry) + Send + Sync);
+
+/// Delay before retrying after failed attempt `attempt`, doubling from one second up to 30,
+/// of which up to half is random so clients that failed together don't retry together.
+fn backoff(attempt: u32) -> Duration {
+    let delay = MIN_BACKOFF
+        .saturating_mul(1 << attempt.saturating_sub(1).min(8))
+        .min(MAX_BACKOFF);
+
+    let random = RandomState::new().build_hasher().finish() % 1000;
+    delay.mul_f64(0.5 + random as f64 / 2000.0)
+}
+
+/// Whether a request that failed with `err` may work when tried again.
+fn is_transient(err: &reqwest::Error) -> bool {
+    match err.status() {
+        Some(status) => is_transient_status(status),
+        None => err.is_timeout() || err.is_connect() || err.is_request() || err.is_body(),
+    }
+}
+
+fn is_transient_status(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::REQUEST_TIMEOUT
 }
 
-impl Getter {
-    fn new(url: &str) -> Self {
+/// Describes an error of a response body, a timeout means the transfer stalled.
+fn body_error(err: reqwest::Error) -> String {
+    match err.is_timeout() {
+        true => format!("download stalled, no data for {}s", STALL_TIMEOUT.as_secs()),
+        false => format!("download interrupted: {err}"),
+    }
+}
+
+/// Client shared by every request.
+fn client() -> Result<&'static Client, String> {
+    static CLIENT: OnceLock<Result<Client, String>> = OnceLock::new();
+
+    CLIENT
+        .get_or_init(|| {
+            Client::builder()
+                .connect_timeout(CONNECT_TIMEOUT)
+                .read_timeout(STALL_TIMEOUT)
+                .build()
+                .map_err(|err| format!("could not create the HTTP client: {err}"))
+        })
+        .as_ref()
+        .map_err(Clone::clone)
+}
+
+/// Browser headers the builder expects, it turns away requests that don't look like one.
+fn builder_headers() -> &'static HeaderMap {
+    static HEADERS: OnceLock<HeaderMap> = OnceLock::new();
+
+    HEADERS.get_or_init(|| {
         let headers = json!( {
               "accept": "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7",
-              "accept-language": "en-US,en;q=0.9,es-419;q=0.8,es;q=0.7,pt;q=0.6,fr;q=0.5",
+              "accept-language": "en-US,en;q=0.9",
               "cache-control": "no-cache",
               "pragma": "no-cache",
               "sec-ch-ua": "\"Google Chrome\";v=\"123\", \"Not:A-Brand\";v=\"8\", \"Chromium\";v=\"123\"",
@@ -30,71 +136,856 @@ impl Getter {
               "sec-fetch-site": "same-site",
               "sec-fetch-user": "?1",
               "upgrade-insecure-requests": "1",
-              "cookie": "_ga_MCBBT8QGSN=GS1.1.1706631626.3.0.1706631628.0.0.0; _ga=GA1.2.753018188.1701969904; _ga_L6Q2GW7H9J=GS1.2.1708030780.35.0.1708030780.0.0.0",
               "Referer": "https://www.blender.org/download/",
               "Referrer-Policy": "no-referrer-when-downgrade",
               "User-Agent": "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36"
             }
         );
 
-        let mut request = reqwest::Request::new(reqwest::Method::GET, Url::parse(url).unwrap());
-        let request_headers = request.headers_mut();
-
-        for (key, value) in headers.as_object().unwrap() {
-            request_headers.insert::<HeaderName>(
-                key.parse().unwrap(),
-                value.as_str().unwrap().parse().unwrap(),
-            );
+        let mut request_headers = HeaderMap::new();
+        for (key, value) in headers.as_object().into_iter().flatten() {
+            if let (Ok(key), Some(Ok(value))) = (
+                key.parse::<HeaderName>(),
+                value.as_str().map(HeaderValue::from_str),
+            ) {
+                request_headers.insert(key, value);
+            }
         }
+        request_headers
+    })
+}
+
+/// GET request for `url`, with the browser headers when it goes to the builder.
+fn get(client: &Client, url: &str) -> RequestBuilder {
+    let builder = Url::parse(url).is_ok_and(|url| {
+        url.host_str().is_some_and(|host| {
+            host == "builder.blender.org" || host.ends_with(".builder.blender.org")
+        })
+    });
 
-        Getter { request }
+    match builder {
+        true => client.get(url).headers(builder_headers().clone()),
+        false => client.get(url),
     }
 }
 
-pub async fn get_links(config: &Config) -> Result<Vec<BlenderVersion>, String> {
-    let getter = Getter::new(&config.link);
+/// Runs `request` until it succeeds, fails for good or runs out of attempts, waiting
+/// with [`backoff`] in between.
+async fn with_retries<T, F, Fut>(on_retry: OnRetry<'_>, mut request: F) -> Result<T, String>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, reqwest::Error>>,
+{
+    let mut attempt = 1;
 
-    let r = match reqwest::Client::new().execute(getter.request).await {
-        Ok(r) => r,
-        Err(err) => {
-            println!("Error getting Request");
-            dbg!(err.to_string());
-            return Err(err.to_string());
+    loop {
+        match request().await {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt < MAX_ATTEMPTS && is_transient(&err) => {
+                let delay = backoff(attempt);
+                on_retry(&Retry {
+                    attempt,
+                    delay,
+                    error: err.to_string(),
+                });
+
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(err) => return Err(err.to_string()),
         }
+    }
+}
+
+async fn get_text(url: &str, on_retry: OnRetry<'_>) -> Result<String, String> {
+    let url = Url::parse(url).map_err(|err| format!("invalid url {url}: {err}"))?;
+
+    let client = client()?;
+
+    with_retries(on_retry, || async {
+        get(client, url.as_str())
+            .timeout(TEXT_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await
+    })
+    .await
+}
+
+/// Builds of the sources that could be listed, with an error for each that couldn't.
+#[derive(Debug, Default)]
+pub struct Listing {
+    pub versions: Vec<BlenderVersion>,
+    /// "<source>: <error>" for every failed source.
+    pub failed: Vec<String>,
+}
+
+/// Lists the builds of every configured source, newest first. Failed requests are retried,
+/// `on_retry` is called before each retry. Only fails when no source could be listed.
+pub async fn get_links(config: &Config, on_retry: OnRetry<'_>) -> Result<Listing, String> {
+    let sources = config.sources();
+    let listings = join_all(
+        sources
+            .iter()
+            .map(|source| get_source_links(source, config, on_retry)),
+    )
+    .await;
+
+    let mut seen = HashSet::new();
+    let mut links = Vec::new();
+    let mut failed = Vec::new();
+
+    for (source, listing) in sources.iter().zip(listings) {
+        let listing = match listing {
+            Ok(listing) => listing,
+            Err(err) => {
+                failed.push(format!("{}: {err}", source.name()));
+                continue;
+            }
+        };
+
+        for mut version in listing {
+            // the same build can be listed by several sources, the first one wins
+            if seen.insert(version.link.clone()) {
+                version.source = source.name().to_owned();
+                links.push(version);
+            }
+        }
+    }
+
+    if failed.len() == sources.len() {
+        return Err(failed.join("; "));
+    }
+
+    links.sort_by_key(|version| Reverse((version.version, version.release)));
+    Ok(Listing {
+        versions: links,
+        failed,
+    })
+}
+
+async fn get_source_links(
+    source: &Source,
+    config: &Config,
+    on_retry: OnRetry<'_>,
+) -> Result<Vec<BlenderVersion>, String> {
+    match source.kind {
+        SourceKind::Daily | SourceKind::Archive | SourceKind::Experimental | SourceKind::Patch => {
+            get_builder_links(source.link(), on_retry).await
+        }
+        SourceKind::Release => get_release_links(source.link(), config, on_retry).await,
+        SourceKind::Feed => get_feed_links(source, on_retry).await,
+    }
+}
+
+/// Lists the assets of a release feed that match the source's pattern.
+async fn get_feed_links(
+    source: &Source,
+    on_retry: OnRetry<'_>,
+) -> Result<Vec<BlenderVersion>, String> {
+    let (link, Some(pattern)) = (source.link(), &source.pattern) else {
+        return Err("feed sources need a `pattern`".to_owned());
     };
+    if link.is_empty() {
+        return Err("feed sources need a `link`".to_owned());
+    }
+
+    let pattern = Regex::new(pattern).map_err(|err| err.to_string())?;
+    let body = get_text(link, on_retry).await?;
+    blender_utils::parse_feed(&body, &pattern, source.name())
+}
 
-    let body = r.text().await.unwrap();
+/// Lists builds from the builder's JSON listing, falling back to scraping the html page.
+async fn get_builder_links(
+    link: &str,
+    on_retry: OnRetry<'_>,
+) -> Result<Vec<BlenderVersion>, String> {
+    let json_url = format!("{link}?format=json&v=1");
+
+    if let Ok(body) = get_text(&json_url, on_retry).await {
+        match blender_utils::parse_json(&body) {
+            Ok(links) if !links.is_empty() => return Ok(links),
+            _ => {}
+        }
+    }
+
+    let body = get_text(link, on_retry).await?;
     blender_utils::select(body)
 }
 
-pub async fn download_with_tx(link: &str, file: &mut File, path: PathBuf, tx: TxMessage) {
-    let getter = Getter::new(link);
+/// Lists official releases of the two newest series and the watched ones from the
+/// `BlenderX.Y/` directories of the release index at `link`.
+async fn get_release_links(
+    link: &str,
+    config: &Config,
+    on_retry: OnRetry<'_>,
+) -> Result<Vec<BlenderVersion>, String> {
+    let body = get_text(link, on_retry).await?;
+
+    let series = blender_utils::release_series(&body);
+    let wanted = series.iter().enumerate().filter(|(idx, series)| {
+        *idx < 2
+            || config
+                .versions
+                .iter()
+                .any(|watched| watched == *series || watched.starts_with(&format!("{series}.")))
+    });
+
+    let mut links = Vec::new();
+    for (_, series) in wanted {
+        links.extend(get_series_releases(link, series, on_retry).await?);
+    }
+
+    Ok(links)
+}
+
+/// Lists the official releases in the `BlenderX.Y/` directory of `series`.
+pub async fn get_series_releases(
+    link: &str,
+    series: &str,
+    on_retry: OnRetry<'_>,
+) -> Result<Vec<BlenderVersion>, String> {
+    let dir = Url::parse(link)
+        .and_then(|base| base.join(&format!("Blender{series}/")))
+        .map_err(|err| err.to_string())?;
+
+    let body = get_text(dir.as_str(), on_retry).await?;
+    Ok(blender_utils::parse_release_index(&body, &dir))
+}
+
+/// SHA256 of the archive of `version`, as published by its listing, in the `.sha256` file next
+/// to it, or for official releases in the `blender-X.Y.Z.sha256` file covering every platform.
+async fn get_checksum(version: &BlenderVersion, on_retry: OnRetry<'_>) -> Result<String, String> {
+    if let Some(digest) = &version.sha256 {
+        return Ok(digest.clone());
+    }
+
+    let link = &version.link;
+    let (dir, file_name) = link.rsplit_once('/').unwrap_or_default();
+
+    let mut candidates = vec![format!("{link}.sha256")];
+    if let Some(release) = BlenderMatcher::new()
+        .match_str(file_name)
+        .filter(|version| version.hash.is_empty())
+    {
+        candidates.insert(0, format!("{dir}/blender-{}.sha256", release.version));
+    }
+
+    let mut result = Err(format!("no checksum file for {link}"));
+    for candidate in candidates {
+        result = get_text(&candidate, on_retry).await.and_then(|body| {
+            parse_checksum(&body, file_name)
+                .ok_or_else(|| format!("no checksum for {file_name} in {candidate}"))
+        });
+
+        if result.is_ok() {
+            break;
+        }
+    }
+    result
+}
+
+/// Finds the digest of `file_name` in a `sha256sum` style file, a lone digest matches any name.
+fn parse_checksum(body: &str, file_name: &str) -> Option<String> {
+    body.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let digest = fields.next()?;
+
+        let matches = match fields.next() {
+            Some(name) => name.trim_start_matches('*') == file_name,
+            None => true,
+        };
+
+        let valid = digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit());
+        (matches && valid).then(|| digest.to_lowercase())
+    })
+}
+
+/// Path of the partial file an archive is downloaded into before it is verified.
+pub fn part_path(path: &Path) -> PathBuf {
+    let mut part = path.as_os_str().to_owned();
+    part.push(".part");
+    PathBuf::from(part)
+}
+
+enum DownloadError {
+    /// The transfer stopped early, the partial file can be resumed.
+    Interrupted(String),
+    /// The request failed in a way retrying won't fix, the partial file is kept.
+    Rejected(String),
+    /// The data is wrong, the partial file has to be discarded.
+    Invalid(String),
+    /// The job was paused, the transfer resumes from the partial file.
+    Paused,
+    Cancelled,
+}
+
+/// Sends an event of job `id`, the receiver is only gone when the app is shutting down.
+pub(crate) async fn send_event(tx: &TxMessage, id: JobId, event: JobEvent) {
+    let _ = tx.send(Message::Job(id, event)).await;
+}
+
+/// Waits for the next control change, or forever once the manager is gone.
+async fn control_changed(control: &mut Receiver<JobControl>) -> JobControl {
+    if control.changed().await.is_err() {
+        std::future::pending::<()>().await;
+    }
+    *control.borrow_and_update()
+}
+
+fn hash_existing(file: &mut File, hasher: &mut Sha256) -> std::io::Result<usize> {
+    file.seek(SeekFrom::Start(0))?;
+    let mut buf = vec![0; 1 << 16];
+    let mut total = 0;
 
-    let mut r: reqwest::Response = match reqwest::Client::new().execute(getter.request).await {
-        Ok(r) => r,
-        Err(err) => {
-            tx.send(Message::Error(err.to_string())).await.unwrap();
-            return;
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            return Ok(total);
         }
+        hasher.update(&buf[..n]);
+        total += n;
+    }
+}
+
+async fn download(
+    id: JobId,
+    version: &BlenderVersion,
+    file: &mut File,
+    tx: &TxMessage,
+    control: &mut Receiver<JobControl>,
+) -> Result<String, DownloadError> {
+    use DownloadError::{Cancelled, Interrupted, Invalid, Paused, Rejected};
+
+    match *control.borrow_and_update() {
+        JobControl::Run => {}
+        JobControl::Pause => return Err(Paused),
+        JobControl::Cancel => return Err(Cancelled),
+    }
+
+    let on_retry = |retry: &Retry| {
+        let _ = tx.try_send(Message::Job(id, JobEvent::Retry(retry.clone())));
     };
+    let expected = get_checksum(version, &on_retry)
+        .await
+        .map_err(|err| Rejected(format!("could not get checksum: {err}")))?;
+
+    let mut hasher = Sha256::new();
+    let mut existing = hash_existing(file, &mut hasher).map_err(|err| Invalid(err.to_string()))?;
+
+    let client = client().map_err(Rejected)?;
+    let mut request = get(client, &version.link);
+    if existing > 0 {
+        request = request.header(RANGE, format!("bytes={existing}-"));
+    }
 
-    let len: usize = r.content_length().unwrap() as usize;
+    let mut r = request
+        .send()
+        .await
+        .map_err(|err| match is_transient(&err) {
+            true => Interrupted(err.to_string()),
+            false => Rejected(err.to_string()),
+        })?;
+
+    match r.status() {
+        StatusCode::PARTIAL_CONTENT => {
+            let status = JobEvent::Status(format!("resuming from {existing} bytes"));
+            send_event(tx, id, status).await;
+        }
+        StatusCode::RANGE_NOT_SATISFIABLE => {
+            // the partial file already holds the whole archive
+            let digest = hex::encode(hasher.finalize());
+            if digest != expected {
+                return Err(Invalid(format!(
+                    "checksum mismatch: expected {expected}, got {digest}"
+                )));
+            }
+            return Ok(digest);
+        }
+        status if status.is_success() => {
+            // the server ignored the range, start over
+            if existing > 0 {
+                file.set_len(0).map_err(|err| Invalid(err.to_string()))?;
+                hasher = Sha256::new();
+                existing = 0;
+            }
+        }
+        status if is_transient_status(status) => {
+            return Err(Interrupted(format!("request failed with {status}")))
+        }
+        status => return Err(Rejected(format!("request failed with {status}"))),
+    }
+
+    let Some(len) = r.content_length() else {
+        return Err(Rejected(
+            "server did not report the content length".to_owned(),
+        ));
+    };
+    let len = existing + len as usize;
 
     let len_mb = len as f32 / 1000000.0;
 
     let size = format!("{} {len_mb:.1}mb ({len} bytes)", "Content Size");
 
-    tx.send(Message::VersionUpdate(size)).await.unwrap();
+    send_event(tx, id, JobEvent::Status(size)).await;
+
+    let mut tracker = ProgressTracker::new(len, existing);
+
+    loop {
+        let chunk = tokio::select! {
+            chunk = r.chunk() => chunk,
+            control = control_changed(control) => match control {
+                JobControl::Run => continue,
+                JobControl::Pause => return Err(Paused),
+                JobControl::Cancel => return Err(Cancelled),
+            },
+        };
+
+        let Some(chunk) = chunk.map_err(|err| Interrupted(body_error(err)))? else {
+            break;
+        };
+
+        file.write_all(&chunk)
+            .map_err(|err| Rejected(err.to_string()))?;
+        hasher.update(&chunk);
+
+        if let Some(progress) = tracker.update(chunk.len()) {
+            send_event(tx, id, JobEvent::Progress(progress)).await;
+        }
+    }
+
+    send_event(tx, id, JobEvent::Progress(tracker.flush())).await;
+
+    if tracker.total_read < len {
+        return Err(Interrupted(format!(
+            "download interrupted: expected {len} bytes, got {}",
+            tracker.total_read
+        )));
+    }
+
+    if tracker.total_read > len {
+        return Err(Invalid(format!(
+            "size mismatch: expected {len} bytes, got {}",
+            tracker.total_read
+        )));
+    }
+
+    let digest = hex::encode(hasher.finalize());
+    if digest != expected {
+        return Err(Invalid(format!(
+            "checksum mismatch: expected {expected}, got {digest}"
+        )));
+    }
+
+    Ok(digest)
+}
+
+/// Downloads `version` into the `.part` file of `path`, resuming it if it exists, and moves it
+/// to `path` once its size and SHA256 are verified, returning the SHA256. Interrupted downloads keep their `.part`
+/// file so the next attempt can resume. Progress is reported as events of job `id`.
+///
+/// Interrupted transfers are resumed after a [`backoff`], up to [`MAX_ATTEMPTS`] times in a
+/// row without progress.
+///
+/// Pausing drops the connection and resumes with a range request. Cancelling returns an
+/// error and leaves the `.part` file for the caller to keep or remove.
+pub async fn download_with_tx(
+    id: JobId,
+    version: &BlenderVersion,
+    file: &mut File,
+    path: &Path,
+    tx: &TxMessage,
+    control: &mut Receiver<JobControl>,
+) -> Result<String, String> {
+    let part = part_path(path);
+    let part_len = |file: &File| file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+
+    let mut attempt = 1;
+
+    loop {
+        let before = part_len(file);
+
+        match download(id, version, file, tx, control).await {
+            Ok(digest) => {
+                std::fs::rename(&part, path).map_err(|err| err.to_string())?;
+                return Ok(digest);
+            }
+            Err(DownloadError::Interrupted(err)) => {
+                // only attempts that got nothing count towards the limit
+                if part_len(file) > before {
+                    attempt = 1;
+                }
+                if attempt >= MAX_ATTEMPTS {
+                    return Err(err);
+                }
+
+                let delay = backoff(attempt);
+                let retry = Retry {
+                    attempt,
+                    delay,
+                    error: err,
+                };
+                send_event(tx, id, JobEvent::Retry(retry)).await;
+                attempt += 1;
+
+                // a pause or cancel during the wait is picked up by the next attempt
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    _ = control_changed(control) => {}
+                }
+            }
+            Err(DownloadError::Rejected(err)) => return Err(err),
+            Err(DownloadError::Invalid(err)) => {
+                let _ = std::fs::remove_file(&part);
+                return Err(err);
+            }
+            Err(DownloadError::Cancelled) => return Err("cancelled".to_owned()),
+            Err(DownloadError::Paused) => {
+                send_event(tx, id, JobEvent::Paused).await;
+
+                let resumed = control
+                    .wait_for(|control| *control != JobControl::Pause)
+                    .await
+                    .map(|control| *control == JobControl::Run)
+                    .unwrap_or(false);
+
+                if !resumed {
+                    return Err("cancelled".to_owned());
+                }
+                send_event(tx, id, JobEvent::Active).await;
+            }
+        }
+    }
+}
+
+/// Blocking reader over the chunks of a download, ends when the sender is dropped.
+struct ChunkReader {
+    rx: mpsc::Receiver<Vec<u8>>,
+    chunk: Vec<u8>,
+    pos: usize,
+}
+
+impl Read for ChunkReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.pos == self.chunk.len() {
+            match self.rx.blocking_recv() {
+                Some(chunk) => {
+                    self.chunk = chunk;
+                    self.pos = 0;
+                }
+                None => return Ok(0),
+            }
+        }
+
+        let n = buf.len().min(self.chunk.len() - self.pos);
+        buf[..n].copy_from_slice(&self.chunk[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Downloads a `.tar.xz` archive of `version` and unpacks it into `target` while it arrives,
+/// without writing the archive to disk. Size and SHA256 are checked once the last byte is
+/// in and the SHA256 is returned, the caller removes what was extracted when this fails.
+///
+/// Pausing holds the connection open until the job is resumed, a streamed download can't
+/// be resumed after it is interrupted.
+pub async fn stream_with_tx(
+    id: JobId,
+    version: &BlenderVersion,
+    target: &Path,
+    tx: &TxMessage,
+    control: &mut Receiver<JobControl>,
+) -> Result<String, String> {
+    let on_retry = |retry: &Retry| {
+        let _ = tx.try_send(Message::Job(id, JobEvent::Retry(retry.clone())));
+    };
+    let expected = get_checksum(version, &on_retry)
+        .await
+        .map_err(|err| format!("could not get checksum: {err}"))?;
+
+    // only the request is retried, a stream that broke off can't be resumed
+    let client = client()?;
+    let mut r = with_retries(&on_retry, || async {
+        get(client, &version.link).send().await?.error_for_status()
+    })
+    .await?;
+
+    let Some(len) = r.content_length() else {
+        return Err("server did not report the content length".to_owned());
+    };
+    let len = len as usize;
+
+    let len_mb = len as f32 / 1000000.0;
+    let size = format!("{} {len_mb:.1}mb ({len} bytes), streaming", "Content Size");
+
+    send_event(tx, id, JobEvent::Status(size)).await;
+
+    let (chunk_tx, chunk_rx) = mpsc::channel(16);
+    let reader = ChunkReader {
+        rx: chunk_rx,
+        chunk: Vec::new(),
+        pos: 0,
+    };
+
+    let target = target.to_owned();
+    let extract_control = control.clone();
+    let mut extractor = tokio::task::spawn_blocking(move || {
+        let cancelled = || *extract_control.borrow() == JobControl::Cancel;
+        extract_stream(reader, &target, cancelled)
+    });
+
+    let mut chunk_tx = Some(chunk_tx);
+    let mut extracted = None;
+
+    let mut hasher = Sha256::new();
+    let mut tracker = ProgressTracker::new(len, 0);
+
+    // the extractor has to be done before returning, the caller may remove its output
+    let result: Result<(), String> = async {
+        loop {
+            let chunk = tokio::select! {
+                chunk = r.chunk() => chunk,
+                state = control_changed(control) => match state {
+                    JobControl::Run => continue,
+                    JobControl::Pause => {
+                        send_event(tx, id, JobEvent::Paused).await;
 
-    let mut tracker = ProgressTracker::new(len);
+                        let resumed = control
+                            .wait_for(|control| *control != JobControl::Pause)
+                            .await
+                            .map(|control| *control == JobControl::Run)
+                            .unwrap_or(false);
 
-    let mut n: usize;
-    while let Ok(Some(chunk)) = r.chunk().await {
-        n = file.write(&chunk).unwrap();
-        if let Some(s) = tracker.update(n) {
-            tx.send(Message::VersionUpdate(s)).await.unwrap();
+                        if !resumed {
+                            return Err("cancelled".to_owned());
+                        }
+                        send_event(tx, id, JobEvent::Active).await;
+                        continue;
+                    }
+                    JobControl::Cancel => return Err("cancelled".to_owned()),
+                },
+            };
+
+            let Some(chunk) = chunk.map_err(body_error)? else {
+                break;
+            };
+
+            hasher.update(&chunk);
+
+            if let Some(sender) = &chunk_tx {
+                if sender.send(chunk.to_vec()).await.is_err() {
+                    // the extractor stopped early, either on an error or at the end of the tar
+                    chunk_tx = None;
+                    extracted = Some((&mut extractor).await);
+                    if let Some(Ok(Err(err))) = &extracted {
+                        return Err(err.to_string());
+                    }
+                }
+            }
+
+            if let Some(progress) = tracker.update(chunk.len()) {
+                send_event(tx, id, JobEvent::Progress(progress)).await;
+            }
         }
+
+        send_event(tx, id, JobEvent::Progress(tracker.flush())).await;
+
+        Ok(())
+    }
+    .await;
+
+    drop(chunk_tx);
+    let extracted = match extracted {
+        Some(extracted) => extracted,
+        None => extractor.await,
+    };
+    result?;
+    extracted
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())?;
+
+    if tracker.total_read != len {
+        return Err(format!(
+            "size mismatch: expected {len} bytes, got {}",
+            tracker.total_read
+        ));
+    }
+
+    let digest = hex::encode(hasher.finalize());
+    if digest != expected {
+        return Err(format!(
+            "checksum mismatch: expected {expected}, got {digest}"
+        ));
+    }
+
+    Ok(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::OpenOptions;
+    use std::sync::{Arc, Mutex};
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::watch;
+
+    use super::*;
+
+    /// Serves `body` on a local port, answering `Range` requests when `ranges` is set.
+    /// Returns the base url and the range start of every request.
+    async fn serve(body: Vec<u8>, ranges: bool) -> (String, Arc<Mutex<Vec<Option<usize>>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = requests.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                    match socket.read(&mut buf).await.unwrap() {
+                        0 => break,
+                        n => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+
+                let request = String::from_utf8_lossy(&request).to_lowercase();
+                let start = request
+                    .lines()
+                    .find_map(|line| line.strip_prefix("range: bytes="))
+                    .and_then(|range| range.trim_end_matches('-').parse::<usize>().ok());
+                seen.lock().unwrap().push(start);
+
+                let len = body.len();
+                let (status, range, content) = match start.filter(|_| ranges) {
+                    Some(start) if start >= len => (
+                        "416 Range Not Satisfiable",
+                        format!("bytes */{len}"),
+                        &body[..0],
+                    ),
+                    Some(start) => (
+                        "206 Partial Content",
+                        format!("bytes {start}-{}/{len}", len - 1),
+                        &body[start..],
+                    ),
+                    None => ("200 OK", format!("bytes 0-{}/{len}", len - 1), &body[..]),
+                };
+
+                let head = format!(
+                    "HTTP/1.1 {status}\r\ncontent-range: {range}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                    content.len()
+                );
+                socket.write_all(head.as_bytes()).await.unwrap();
+                socket.write_all(content).await.unwrap();
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        (url, requests)
+    }
+
+    fn archive() -> Vec<u8> {
+        (0..200_000u32).map(|idx| (idx % 251) as u8).collect()
+    }
+
+    /// Downloads `body` with `part` already in the partial file, returning the result, the
+    /// finished file and the range start of every request.
+    async fn download_resuming(
+        body: &[u8],
+        part: &[u8],
+        ranges: bool,
+    ) -> (Result<String, String>, Vec<u8>, Vec<Option<usize>>) {
+        let dir = tempfile::tempdir().unwrap();
+        let (url, requests) = serve(body.to_vec(), ranges).await;
+
+        let version = BlenderVersion {
+            link: format!("{url}/blender-4.2.3-linux-x64.tar.xz"),
+            extension: "tar.xz".to_owned(),
+            sha256: Some(hex::encode(Sha256::digest(body))),
+            ..Default::default()
+        };
+
+        let path = dir.path().join(version.file_name());
+        std::fs::write(part_path(&path), part).unwrap();
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .open(part_path(&path))
+            .unwrap();
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+        tokio::spawn(async move { while rx.recv().await.is_some() {} });
+        let (_control_tx, mut control) = watch::channel(JobControl::Run);
+
+        let result =
+            download_with_tx(0, &version, &mut file, &path, &Arc::new(tx), &mut control).await;
+        let downloaded = std::fs::read(&path).unwrap_or_default();
+        let requests = requests.lock().unwrap().clone();
+
+        (result, downloaded, requests)
     }
 
-    tx.send(Message::VersionResult(path)).await.unwrap();
+    #[tokio::test]
+    async fn partial_download_is_resumed_with_a_range_request() {
+        let body = archive();
+        let (result, downloaded, requests) = download_resuming(&body, &body[..50_000], true).await;
+
+        assert_eq!(result, Ok(hex::encode(Sha256::digest(&body))));
+        assert_eq!(downloaded, body);
+        assert_eq!(requests, [Some(50_000)]);
+    }
+
+    #[tokio::test]
+    async fn complete_partial_download_is_not_fetched_again() {
+        let body = archive();
+        let (result, downloaded, requests) = download_resuming(&body, &body, true).await;
+
+        assert_eq!(result, Ok(hex::encode(Sha256::digest(&body))));
+        assert_eq!(downloaded, body);
+        assert_eq!(requests, [Some(body.len())]);
+    }
+
+    #[tokio::test]
+    async fn download_starts_over_when_ranges_are_ignored() {
+        let body = archive();
+        let (result, downloaded, requests) = download_resuming(&body, &body[..50_000], false).await;
+
+        assert_eq!(result, Ok(hex::encode(Sha256::digest(&body))));
+        assert_eq!(downloaded, body);
+        assert_eq!(requests, [Some(50_000)]);
+    }
+
+    #[tokio::test]
+    async fn feed_source_is_listed() {
+        let body = include_bytes!("../tests/fixtures/feed_upbge.json").to_vec();
+        let (url, _) = serve(body, false).await;
+
+        let mut source = Source::new(SourceKind::Feed);
+        source.name = Some("upbge".to_owned());
+        source.link = Some(format!("{url}/repos/UPBGE/upbge/releases"));
+        source.pattern = Some(
+            r"^upbge-(?<version>\d+\.\d+\.\d+)-(?<os>[a-z]+)-(?<arch>[a-z0-9_]+)\.(?<extension>tar\.xz|zip|dmg)$"
+                .to_owned(),
+        );
+
+        let versions = get_feed_links(&source, &|_| {}).await.unwrap();
+
+        assert_eq!(versions.len(), 4);
+        assert!(versions.iter().all(|version| version.branch == "upbge"));
+    }
+
+    #[tokio::test]
+    async fn feed_source_needs_a_pattern() {
+        let mut source = Source::new(SourceKind::Feed);
+        source.link = Some("http://127.0.0.1:9/releases".to_owned());
+
+        assert!(get_feed_links(&source, &|_| {}).await.is_err());
+    }
 }
@@ -0,0 +1,230 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use tokio::sync::oneshot;
+
+use crate::{
+    blender_utils::executable,
+    config::LaunchProfile,
+    tui::{InstanceEvent, InstanceId, Message, TxMessage},
+    LocalBlenderVersion,
+};
+
+/// Starts installs as detached processes and reports them as [`Message::Instance`] events.
+///
+/// Output goes to a log file in `<Config::path>/.logs`, so instances keep running after the
+/// manager exits.
+pub struct Launcher {
+    base: PathBuf,
+    tx: TxMessage,
+    next_id: InstanceId,
+    kills: HashMap<InstanceId, oneshot::Sender<()>>,
+}
+
+impl Launcher {
+    pub fn new(base: PathBuf, tx: TxMessage) -> Self {
+        Launcher {
+            base,
+            tx,
+            next_id: 0,
+            kills: HashMap::new(),
+        }
+    }
+
+    pub fn launch(
+        &mut self,
+        local: &LocalBlenderVersion,
+        profile: Option<&LaunchProfile>,
+    ) -> Result<InstanceId, String> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        // exited instances dropped their receiver
+        self.kills.retain(|_, kill| !kill.is_closed());
+
+        let log = self.log_path(local)?;
+        let stdout = File::create(&log).map_err(|err| err.to_string())?;
+        let stderr = stdout.try_clone().map_err(|err| err.to_string())?;
+
+        let mut child = blender_command(&local.path, profile)
+            .stdin(Stdio::null())
+            .stdout(stdout)
+            .stderr(stderr)
+            // keeps ctrl-c in the terminal from reaching blender
+            .process_group(0)
+            .spawn()
+            .map_err(|err| format!("could not launch {}: {err}", local.path.display()))?;
+
+        let (kill_tx, kill_rx) = oneshot::channel();
+        self.kills.insert(id, kill_tx);
+
+        let pid = child.id();
+        let started = InstanceEvent::Started {
+            pid: pid.unwrap_or_default(),
+            version: Box::new(local.blender_version.clone()),
+            log,
+        };
+
+        let tx = self.tx.clone();
+        tokio::spawn(async move {
+            let _ = tx.send(Message::Instance(id, started)).await;
+
+            let status = tokio::select! {
+                status = child.wait() => status,
+                Ok(()) = kill_rx => {
+                    kill_group(&mut child, pid);
+                    child.wait().await
+                }
+            };
+
+            let status = match status {
+                Ok(status) => status.to_string(),
+                Err(err) => err.to_string(),
+            };
+            let _ = tx
+                .send(Message::Instance(id, InstanceEvent::Exited(status)))
+                .await;
+        });
+
+        Ok(id)
+    }
+
+    pub fn kill(&mut self, id: InstanceId) {
+        if let Some(kill) = self.kills.remove(&id) {
+            let _ = kill.send(());
+        }
+    }
+
+    fn log_path(&self, local: &LocalBlenderVersion) -> Result<PathBuf, String> {
+        let logs = self.base.join(".logs");
+        std::fs::create_dir_all(&logs).map_err(|err| err.to_string())?;
+
+        let name = local
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        Ok(logs.join(format!("{name}-{time}.log")))
+    }
+}
+
+/// Terminates the process group `child` leads, so processes it started exit with it.
+fn kill_group(child: &mut tokio::process::Child, pid: Option<u32>) {
+    let killed = pid.is_some_and(|pid| {
+        // SAFETY: killpg only sends a signal and doesn't touch memory
+        unsafe { libc::killpg(pid as libc::pid_t, libc::SIGTERM) == 0 }
+    });
+
+    if !killed {
+        let _ = child.start_kill();
+    }
+}
+
+/// Command running the `blender` executable of the install in `path` with `profile`.
+pub fn blender_command(path: &Path, profile: Option<&LaunchProfile>) -> tokio::process::Command {
+    let mut command = tokio::process::Command::new(executable(path));
+
+    if let Some(profile) = profile {
+        command.args(&profile.args).envs(&profile.env);
+
+        if let Some(working_dir) = &profile.working_dir {
+            command.current_dir(working_dir);
+        }
+    }
+
+    command
+}
+
+/// Reads what was written to a log file after `offset`, at most its last `max` bytes.
+///
+/// Returns where the bytes start, which differs from `offset` when the new output was cut
+/// to `max` or the log was truncated and is read from the start again.
+pub fn read_log(path: &Path, offset: u64, max: u64) -> (u64, Vec<u8>) {
+    let len = std::fs::metadata(path)
+        .map(|metadata| metadata.len())
+        .unwrap_or(offset);
+    if len == offset {
+        return (offset, Vec::new());
+    }
+
+    let from = if len < offset { 0 } else { offset };
+    let start = from.max(len.saturating_sub(max));
+
+    let mut bytes = Vec::new();
+    let read = File::open(path).and_then(|mut file| {
+        file.seek(SeekFrom::Start(start))?;
+        file.read_to_end(&mut bytes)
+    });
+
+    match read {
+        Ok(_) => (start, bytes),
+        Err(_) => (offset, Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    /// Whether `pid` exited, counting zombies nobody reaped yet.
+    fn exited(pid: &str) -> bool {
+        match std::fs::read_to_string(format!("/proc/{pid}/stat")) {
+            Ok(stat) => stat
+                .rsplit_once(") ")
+                .is_some_and(|(_, rest)| rest.starts_with('Z')),
+            Err(_) => true,
+        }
+    }
+
+    #[tokio::test]
+    async fn kill_ends_processes_the_instance_started() {
+        let dir = tempfile::tempdir().unwrap();
+        let grandchild = dir.path().join("pid");
+
+        let mut child = tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!(
+                "sleep 30 & echo $! > {}; wait",
+                grandchild.display()
+            ))
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+
+        let mut sleep = String::new();
+        for _ in 0..100 {
+            sleep = std::fs::read_to_string(&grandchild).unwrap_or_default();
+            if sleep.ends_with('\n') {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        let sleep = sleep.trim();
+        assert!(!exited(sleep));
+
+        kill_group(&mut child, pid);
+        child.wait().await.unwrap();
+
+        for _ in 0..100 {
+            if exited(sleep) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("sleep {sleep} survived the kill");
+    }
+}
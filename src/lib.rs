@@ -1,19 +1,87 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
 pub mod blender_utils;
+pub mod cli;
 pub mod config;
+pub mod desktop;
+mod extract;
 mod getter;
+mod launcher;
+pub mod links;
+mod manager;
+pub mod manifest;
+pub mod prune;
+pub mod source;
 mod tracker;
 pub mod tui;
+pub mod version;
+
+pub use extract::{clean_staging, ExtractError, ExtractProgress};
+pub use getter::{Listing, Retry};
+pub use tracker::Progress;
+pub use version::{Release, Version};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct BlenderVersion {
-    pub version: String,
-    pub release: String,
+    pub version: Version,
+    pub release: Release,
     pub branch: String,
+    /// Short commit hash, empty for official releases.
+    pub hash: String,
     pub os: String,
+    pub arch: String,
+    /// Archive extension such as "tar.xz", empty for extracted directories.
+    pub extension: String,
+    /// Build time in seconds since the unix epoch, when the listing reports it.
+    pub build_date: Option<u64>,
     pub link: String,
+    /// Archive size in bytes, when the listing reports it.
+    pub size: Option<u64>,
+    /// Name of the source the build was listed by.
+    pub source: String,
+    /// SHA256 of the archive, when the listing publishes it.
+    pub sha256: Option<String>,
+}
+
+impl BlenderVersion {
+    /// Name of the archive file, the last segment of the link.
+    pub fn file_name(&self) -> &str {
+        self.link.rsplit('/').next().unwrap_or_default()
+    }
+
+    /// Name of the directory the archive extracts to.
+    pub fn dir_name(&self) -> String {
+        let file_name = self.file_name();
+
+        file_name
+            .strip_suffix(&format!(".{}", self.extension))
+            .unwrap_or(file_name)
+            .to_owned()
+    }
+
+    /// Whether the build is for `os` and `arch`, either of which may be "all".
+    pub fn targets(&self, os: &str, arch: &str) -> bool {
+        (os == "all" || self.os == os) && (arch == "all" || self.arch == arch)
+    }
+
+    /// Whether the build runs on this machine and can be installed rather than only staged.
+    pub fn is_host(&self) -> bool {
+        self.targets(&blender_utils::host_os(), &blender_utils::host_arch())
+    }
 }
 
+#[derive(Clone, Serialize)]
 pub struct LocalBlenderVersion {
     pub blender_version: BlenderVersion,
+    /// How long ago it was installed, e.g. "3 days ago", for display.
+    #[serde(skip)]
     pub created: String,
+    /// Install time in seconds since the unix epoch.
+    pub installed: u64,
+    pub path: PathBuf,
+    pub protected: bool,
+    /// Whether `blender-current` points at this install.
+    pub default: bool,
 }
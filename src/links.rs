@@ -0,0 +1,119 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    config::Config,
+    manifest::{self, Manifest},
+};
+
+/// Link in `Config::path` to the default install.
+pub const CURRENT_LINK: &str = "blender-current";
+
+/// Points `blender-current` and a `blender-X.Y` link per series in `config.path` at the
+/// default install, or the newest one when none is set. Links of series without installs
+/// are removed. With `Config::bin_links` the `blender` executables are also linked from
+/// `~/.local/bin`.
+pub fn update_links(config: &Config) -> Result<(), String> {
+    let base = PathBuf::from(&config.path);
+    let mut installs = manifest::sync(&base)?;
+    // forks installed from feeds don't take over the blender links
+    installs.retain(|(path, _)| {
+        path.file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with("blender-"))
+    });
+
+    let mut links: Vec<(String, &Path)> = Vec::new();
+
+    if let Some((path, _)) = pick(installs.iter(), |manifest| manifest.default) {
+        links.push((CURRENT_LINK.to_owned(), path));
+    }
+
+    let mut series: Vec<String> = installs
+        .iter()
+        .map(|(_, manifest)| manifest.version.series())
+        .collect();
+    series.sort();
+    series.dedup();
+
+    for series in series {
+        let in_series = installs
+            .iter()
+            .filter(|(_, manifest)| manifest.version.series() == series);
+
+        if let Some((path, _)) = pick(in_series, |manifest| manifest.pinned) {
+            links.push((format!("blender-{series}"), path));
+        }
+    }
+
+    for (name, path) in &links {
+        let target = path.file_name().unwrap_or_default();
+        replace_link(Path::new(target), &base.join(name))?;
+    }
+    remove_stale(&base, &base, &links)?;
+
+    if config.bin_links.unwrap_or(false) {
+        let bin = bin_dir()?;
+        std::fs::create_dir_all(&bin).map_err(|err| err.to_string())?;
+        let base = std::fs::canonicalize(&base).map_err(|err| err.to_string())?;
+
+        for (name, _) in &links {
+            replace_link(&base.join(name).join("blender"), &bin.join(name))?;
+        }
+        remove_stale(&bin, &base, &links)?;
+    }
+
+    Ok(())
+}
+
+/// The install `chosen` returns true for, or else the newest one.
+fn pick<'a>(
+    installs: impl Iterator<Item = &'a (PathBuf, Manifest)>,
+    chosen: impl Fn(&Manifest) -> bool,
+) -> Option<&'a (PathBuf, Manifest)> {
+    installs.max_by_key(|(_, manifest)| {
+        (
+            chosen(manifest),
+            manifest.version,
+            manifest.release,
+            manifest.installed,
+        )
+    })
+}
+
+fn bin_dir() -> Result<PathBuf, String> {
+    let home = std::env::var("HOME").map_err(|_| "HOME is not set".to_owned())?;
+    Ok(PathBuf::from(home).join(".local/bin"))
+}
+
+/// Creates the link next to `link` and renames it over the old one, so it never dangles.
+fn replace_link(target: &Path, link: &Path) -> Result<(), String> {
+    let mut tmp = link.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+
+    let _ = std::fs::remove_file(&tmp);
+    std::os::unix::fs::symlink(target, &tmp).map_err(|err| err.to_string())?;
+    std::fs::rename(&tmp, link).map_err(|err| err.to_string())
+}
+
+/// Removes `blender-*` links in `dir` that point into `base` but are no longer wanted.
+fn remove_stale(dir: &Path, base: &Path, links: &[(String, &Path)]) -> Result<(), String> {
+    for entry in std::fs::read_dir(dir).map_err(|err| err.to_string())? {
+        let entry = entry.map_err(|err| err.to_string())?;
+        let name = entry.file_name().to_string_lossy().to_string();
+
+        if !name.starts_with("blender-") || links.iter().any(|(link, _)| *link == name) {
+            continue;
+        }
+
+        let Ok(target) = std::fs::read_link(entry.path()) else {
+            continue;
+        };
+
+        // links in `base` are relative, the ones in ~/.local/bin point into `base`
+        if target.is_relative() || target.starts_with(base) {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+
+    Ok(())
+}
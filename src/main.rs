@@ -1,8 +1,32 @@
+use clap::Parser;
+use downloader::cli::Cli;
 use downloader::config::parse_config;
 use downloader::tui::TuiApp;
 
 async fn main_async() {
-    let config = parse_config().unwrap();
+    let cli = Cli::parse();
+    let mut config = match parse_config() {
+        Ok(config) => config,
+        Err(err) => {
+            downloader::cli::print_error(&err, cli.json);
+            std::process::exit(1);
+        }
+    };
+    config.platform = cli.platform.or(config.platform);
+    config.arch = cli.arch.or(config.arch);
+
+    downloader::clean_staging(std::path::Path::new(&config.path));
+
+    if let Some(command) = cli.command {
+        match downloader::cli::run(command, config).await {
+            Ok(report) => report.print(cli.json),
+            Err(err) => {
+                downloader::cli::print_error(&err, cli.json);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
 
     let mut app = TuiApp::new(config);
     let mut terminal = downloader::tui::init().unwrap();
@@ -0,0 +1,273 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use tokio::sync::{watch, Semaphore};
+
+use crate::{
+    config::Config,
+    desktop::update_entries,
+    extract::{create_staging, install_staged, staging_dir},
+    getter::{download_with_tx, part_path, send_event, stream_with_tx},
+    links::update_links,
+    manifest::{self, Manifest},
+    prune::{has_retention, prune},
+    source::SourceKind,
+    tui::{extract_and_clean, get_file, JobEvent, JobId, Message, TxMessage},
+    BlenderVersion,
+};
+
+/// What a running job should be doing, changed through the [`DownloadManager`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobControl {
+    Run,
+    Pause,
+    Cancel,
+}
+
+/// Runs download jobs in the background, at most `Config::max_downloads` at a time.
+///
+/// Every job reports its state and progress as [`Message::Job`] events.
+pub struct DownloadManager {
+    config: Config,
+    tx: TxMessage,
+    semaphore: Arc<Semaphore>,
+    next_id: JobId,
+    /// Directory name of the build and control of every queued or active job.
+    controls: HashMap<JobId, (String, watch::Sender<JobControl>)>,
+}
+
+impl DownloadManager {
+    pub fn new(config: Config, tx: TxMessage) -> Self {
+        let permits = config.max_downloads.unwrap_or(2).max(1);
+
+        DownloadManager {
+            config,
+            tx,
+            semaphore: Arc::new(Semaphore::new(permits)),
+            next_id: 0,
+            controls: HashMap::new(),
+        }
+    }
+
+    /// Queues a download and extraction of `version`, returning its job id. Fails when the
+    /// same build is already queued or active, as both jobs would write the same files.
+    pub fn enqueue(&mut self, version: BlenderVersion) -> Result<JobId, String> {
+        // finished jobs dropped their receiver
+        self.controls.retain(|_, (_, control)| !control.is_closed());
+
+        let dir_name = version.dir_name();
+        if self
+            .controls
+            .values()
+            .any(|(queued, _)| *queued == dir_name)
+        {
+            return Err(format!("{dir_name} is already queued"));
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let (control_tx, mut control) = watch::channel(JobControl::Run);
+        self.controls.insert(id, (dir_name, control_tx));
+
+        let config = self.config.clone();
+        let tx = self.tx.clone();
+        let semaphore = self.semaphore.clone();
+
+        tokio::spawn(async move {
+            send_event(&tx, id, JobEvent::Queued(version.clone())).await;
+
+            let _permit = tokio::select! {
+                permit = semaphore.acquire_owned() => permit.unwrap(),
+                _ = wait_cancelled(&mut control) => {
+                    send_event(&tx, id, JobEvent::Cancelled).await;
+                    return;
+                }
+            };
+            send_event(&tx, id, JobEvent::Active).await;
+
+            let result = run_job(id, &version, &config, &tx, &mut control).await;
+            let cancelled = *control.borrow() == JobControl::Cancel;
+
+            let event = match result {
+                Ok(path) => JobEvent::Done(path),
+                Err(_) if cancelled => {
+                    clean_cancelled(&version, &config);
+                    JobEvent::Cancelled
+                }
+                Err(err) => JobEvent::Failed(err),
+            };
+            send_event(&tx, id, event).await;
+        });
+
+        Ok(id)
+    }
+
+    fn set(&self, id: JobId, state: JobControl) {
+        if let Some((_, control)) = self.controls.get(&id) {
+            control.send_if_modified(|control| {
+                // a cancelled job can't be resumed
+                if *control == state || *control == JobControl::Cancel {
+                    return false;
+                }
+                *control = state;
+                true
+            });
+        }
+    }
+
+    pub fn pause(&self, id: JobId) {
+        self.set(id, JobControl::Pause);
+    }
+
+    pub fn resume(&self, id: JobId) {
+        self.set(id, JobControl::Run);
+    }
+
+    pub fn cancel(&self, id: JobId) {
+        self.set(id, JobControl::Cancel);
+    }
+
+    pub fn pause_all(&self) {
+        self.controls.keys().for_each(|id| self.pause(*id));
+    }
+
+    pub fn resume_all(&self) {
+        self.controls.keys().for_each(|id| self.resume(*id));
+    }
+
+    pub fn cancel_all(&self) {
+        self.controls.keys().for_each(|id| self.cancel(*id));
+    }
+}
+
+async fn wait_cancelled(control: &mut watch::Receiver<JobControl>) {
+    if control
+        .wait_for(|control| *control == JobControl::Cancel)
+        .await
+        .is_err()
+    {
+        std::future::pending::<()>().await;
+    }
+}
+
+async fn run_job(
+    id: JobId,
+    version: &BlenderVersion,
+    config: &Config,
+    tx: &TxMessage,
+    control: &mut watch::Receiver<JobControl>,
+) -> Result<PathBuf, String> {
+    if !version.is_host() {
+        // builds for other machines are only downloaded and verified, to be copied over
+        let (mut file, path) = get_file(version, config.clone())?;
+        download_with_tx(id, version, &mut file, &path, tx, control).await?;
+        return Ok(path);
+    }
+
+    let base = PathBuf::from(&config.path);
+    let dir_name = version.dir_name();
+    let staging = create_staging(&base, &dir_name)?;
+
+    // forks are versioned on their own, their data directory is named after the Blender series
+    let fork = config
+        .sources()
+        .iter()
+        .any(|source| source.kind == SourceKind::Feed && source.name() == version.source);
+    let series = version.version.series();
+    let series = (!fork).then_some(series.as_str());
+
+    let result = match unpack(id, version, config, &staging, tx, control).await {
+        Ok(digest) => install_staged(&staging, &base, &dir_name, series)
+            .map(|path| (path, digest))
+            .map_err(|err| err.to_string()),
+        Err(err) => Err(err),
+    };
+
+    let (path, digest) = match result {
+        Ok(installed) => installed,
+        Err(err) => {
+            let _ = std::fs::remove_dir_all(&staging);
+            return Err(err);
+        }
+    };
+
+    let manifest = Manifest::new(version, Some(digest), &path);
+    manifest::record(&base, &dir_name, manifest)?;
+
+    if let Err(err) = update_links(config).and_then(|()| update_entries(config)) {
+        let status = JobEvent::Status(format!("could not update links: {err}"));
+        send_event(tx, id, status).await;
+    }
+
+    if has_retention(config) {
+        let prune_config = config.clone();
+        let pruned = tokio::task::spawn_blocking(move || prune(&prune_config, false))
+            .await
+            .map_err(|err| err.to_string())
+            .and_then(|result| result);
+
+        let status = match pruned {
+            Ok(report) => report.summary(),
+            Err(err) => format!("could not prune: {err}"),
+        };
+        send_event(tx, id, JobEvent::Status(status)).await;
+    }
+
+    Ok(path)
+}
+
+/// Downloads `version` and extracts it into `staging`, streaming it when `Config::stream`
+/// is set. Returns the SHA256 of the archive.
+async fn unpack(
+    id: JobId,
+    version: &BlenderVersion,
+    config: &Config,
+    staging: &Path,
+    tx: &TxMessage,
+    control: &mut watch::Receiver<JobControl>,
+) -> Result<String, String> {
+    if config.stream.unwrap_or(false) && version.extension == "tar.xz" {
+        return stream_with_tx(id, version, staging, tx, control).await;
+    }
+
+    let (mut file, path) = get_file(version, config.clone())?;
+
+    let digest = download_with_tx(id, version, &mut file, &path, tx, control).await?;
+
+    let status = JobEvent::Status("downloaded...extracting...".to_owned());
+    send_event(tx, id, status).await;
+
+    let staging = staging.to_owned();
+    let extract_control = control.clone();
+    let extract_tx = tx.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let report = |progress| {
+            let _ = extract_tx.blocking_send(Message::Job(id, JobEvent::Extracting(progress)));
+        };
+        extract_and_clean(path, &staging, &extract_control, report)
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(|err| err.to_string())?;
+
+    Ok(digest)
+}
+
+/// Removes what a cancelled job left in `config.path`, keeping the downloaded data when
+/// `Config::keep_partial` is set.
+fn clean_cancelled(version: &BlenderVersion, config: &Config) {
+    let base = PathBuf::from(&config.path);
+
+    let _ = std::fs::remove_dir_all(staging_dir(&base, &version.dir_name()));
+
+    if !config.keep_partial.unwrap_or(false) {
+        let path = base.join(version.file_name());
+        let _ = std::fs::remove_file(part_path(&path));
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,254 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    blender_utils::{normalize_arch, normalize_os, BlenderMatcher},
+    BlenderVersion, Release, Version,
+};
+
+/// File in `Config::path` holding the manifest of every install, keyed by directory name.
+pub const MANIFEST_FILE: &str = "installed.json";
+
+/// Jobs finishing at the same time would otherwise overwrite each other's records.
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// What is known about an install.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: Version,
+    pub release: Release,
+    pub branch: String,
+    pub hash: String,
+    #[serde(default)]
+    pub os: String,
+    #[serde(default)]
+    pub arch: String,
+    /// Link the archive was downloaded from, empty when rebuilt from the directory.
+    #[serde(default)]
+    pub source: String,
+    pub sha256: Option<String>,
+    pub build_date: Option<u64>,
+    /// Install time in seconds since the unix epoch.
+    pub installed: u64,
+    /// Size of the install directory in bytes.
+    pub size: u64,
+    /// Chosen as the install its series link points at.
+    #[serde(default)]
+    pub pinned: bool,
+    /// Chosen as the install `blender-current` points at.
+    #[serde(default)]
+    pub default: bool,
+    /// Protected installs can't be removed until the flag is cleared.
+    #[serde(default)]
+    pub protected: bool,
+}
+
+pub type Manifests = BTreeMap<String, Manifest>;
+
+impl Manifest {
+    pub fn new(version: &BlenderVersion, sha256: Option<String>, path: &Path) -> Self {
+        Manifest {
+            version: version.version,
+            release: version.release,
+            branch: version.branch.clone(),
+            hash: version.hash.clone(),
+            os: version.os.clone(),
+            arch: version.arch.clone(),
+            source: version.link.clone(),
+            sha256,
+            build_date: version.build_date,
+            installed: now(),
+            size: dir_size(path),
+            pinned: false,
+            default: false,
+            protected: false,
+        }
+    }
+
+    pub fn blender_version(&self) -> BlenderVersion {
+        BlenderVersion {
+            version: self.version,
+            release: self.release,
+            branch: self.branch.clone(),
+            hash: self.hash.clone(),
+            os: normalize_os(&self.os),
+            arch: normalize_arch(&self.arch),
+            extension: String::new(),
+            build_date: self.build_date,
+            link: self.source.clone(),
+            size: Some(self.size),
+            ..Default::default()
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Total size of the files under `path`, without following links.
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Reads the manifests in `base`, none when the file doesn't exist yet. A file that can't be
+/// read is an error rather than empty, saving over it would lose every flag in it.
+fn load(base: &Path) -> Result<Manifests, String> {
+    let path = base.join(MANIFEST_FILE);
+
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Manifests::new()),
+        Err(err) => return Err(format!("could not read {}: {err}", path.display())),
+    };
+
+    serde_json::from_str(&contents)
+        .map_err(|err| format!("{} is damaged, fix or remove it: {err}", path.display()))
+}
+
+/// Writes the manifests to a temporary file first so a crash can't leave a truncated one.
+fn save(base: &Path, manifests: &Manifests) -> Result<(), String> {
+    let contents = serde_json::to_string_pretty(manifests).map_err(|err| err.to_string())?;
+
+    let tmp = base.join(format!(".{MANIFEST_FILE}.tmp"));
+    std::fs::write(&tmp, contents).map_err(|err| err.to_string())?;
+    std::fs::rename(&tmp, base.join(MANIFEST_FILE)).map_err(|err| err.to_string())
+}
+
+/// Adds or replaces the manifest of the install in `base/dir_name`.
+pub fn record(base: &Path, dir_name: &str, manifest: Manifest) -> Result<(), String> {
+    let _lock = LOCK.lock().unwrap();
+
+    let mut manifests = load(base)?;
+    manifests.insert(dir_name.to_owned(), manifest);
+    save(base, &manifests)
+}
+
+/// Changes the manifest of the install in `base/dir_name`, if it has one.
+pub fn update(base: &Path, dir_name: &str, f: impl FnOnce(&mut Manifest)) -> Result<(), String> {
+    let _lock = LOCK.lock().unwrap();
+
+    let mut manifests = load(base)?;
+    if let Some(manifest) = manifests.get_mut(dir_name) {
+        f(manifest);
+        save(base, &manifests)?;
+    }
+    Ok(())
+}
+
+/// Makes the install in `base/dir_name` the default one and the pinned one of its series.
+pub fn set_default(base: &Path, dir_name: &str) -> Result<(), String> {
+    let _lock = LOCK.lock().unwrap();
+
+    let mut manifests = load(base)?;
+    let Some(series) = manifests
+        .get(dir_name)
+        .map(|manifest| manifest.version.series())
+    else {
+        return Err(format!("{dir_name} is not installed"));
+    };
+
+    for (name, manifest) in manifests.iter_mut() {
+        let chosen = name == dir_name;
+        manifest.default = chosen;
+        if manifest.version.series() == series {
+            manifest.pinned = chosen;
+        }
+    }
+
+    save(base, &manifests)
+}
+
+/// Reads the manifests in `base`, dropping the ones whose directory is gone and rebuilding
+/// missing ones from the directory name.
+pub fn sync(base: &Path) -> Result<Vec<(PathBuf, Manifest)>, String> {
+    let _lock = LOCK.lock().unwrap();
+
+    let matcher = BlenderMatcher::new();
+    let mut manifests = load(base)?;
+    let mut changed = false;
+
+    let mut dirs = Vec::new();
+    for entry in std::fs::read_dir(base).map_err(|err| err.to_string())? {
+        let entry = entry.map_err(|err| err.to_string())?;
+
+        if !entry.file_type().map(|kind| kind.is_dir()).unwrap_or(false) {
+            continue;
+        }
+
+        let dir_name = entry.file_name().to_string_lossy().to_string();
+
+        // recorded installs are kept even when their name isn't Blender's, e.g. forks
+        if !manifests.contains_key(&dir_name) {
+            let Some(version) = matcher.match_str(&dir_name) else {
+                continue;
+            };
+
+            let mut manifest = Manifest::new(&version, None, &entry.path());
+
+            if let Some(modified) = entry
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            {
+                manifest.installed = modified.as_secs();
+            }
+
+            manifests.insert(dir_name.clone(), manifest);
+            changed = true;
+        }
+
+        dirs.push(dir_name);
+    }
+
+    let before = manifests.len();
+    manifests.retain(|dir_name, _| dirs.contains(dir_name));
+    changed |= manifests.len() != before;
+
+    if changed {
+        save(base, &manifests)?;
+    }
+
+    Ok(manifests
+        .into_iter()
+        .map(|(dir_name, manifest)| (base.join(dir_name), manifest))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn damaged_manifest_is_not_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(MANIFEST_FILE);
+        std::fs::create_dir(dir.path().join("blender-4.2.3-linux-x64")).unwrap();
+        std::fs::write(&path, "{ not json").unwrap();
+
+        assert!(sync(dir.path()).is_err());
+        assert!(update(dir.path(), "blender-4.2.3-linux-x64", |_| {}).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{ not json");
+    }
+}
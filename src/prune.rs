@@ -0,0 +1,125 @@
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+
+use crate::{
+    config::Config, desktop::update_entries, links::update_links, manifest, tui::remove_install,
+};
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// Installs removed, or that would be removed on a dry run, and the space they take.
+#[derive(Debug, Default, Serialize)]
+pub struct PruneReport {
+    pub removed: Vec<PathBuf>,
+    pub freed: u64,
+    pub dry_run: bool,
+}
+
+impl PruneReport {
+    pub fn summary(&self) -> String {
+        let freed = self.freed as f32 / 1000000.0;
+
+        match self.dry_run {
+            true => format!(
+                "would remove {} builds, freeing {freed:.1}mb",
+                self.removed.len()
+            ),
+            false => format!("removed {} builds, freed {freed:.1}mb", self.removed.len()),
+        }
+    }
+}
+
+/// Whether any retention setting is configured, without one nothing is pruned.
+pub fn has_retention(config: &Config) -> bool {
+    config.keep_last.is_some() || config.keep_days.is_some()
+}
+
+/// Installs in `config.path` the retention settings don't keep, with their sizes.
+///
+/// Protected, pinned and default installs and the ones in `Config::keep` are always kept.
+/// Otherwise an install is kept when it is one of the `Config::keep_last` newest of its
+/// series and branch, or was installed in the last `Config::keep_days` days.
+pub fn plan(config: &Config) -> Result<Vec<(PathBuf, u64)>, String> {
+    if !has_retention(config) {
+        return Ok(Vec::new());
+    }
+
+    let installs = manifest::sync(Path::new(&config.path))?;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    let mut groups: HashMap<(String, String), Vec<&(PathBuf, manifest::Manifest)>> = HashMap::new();
+    for install in installs.iter() {
+        let manifest = &install.1;
+        let key = (manifest.version.series(), manifest.branch.clone());
+        groups.entry(key).or_default().push(install);
+    }
+
+    let mut removed = Vec::new();
+
+    for mut group in groups.into_values() {
+        group.sort_by_key(|(_, manifest)| {
+            Reverse((
+                manifest.version,
+                manifest.release,
+                manifest.build_date,
+                manifest.installed,
+            ))
+        });
+
+        for (idx, (path, manifest)) in group.into_iter().enumerate() {
+            let dir_name = path.file_name().unwrap_or_default().to_string_lossy();
+
+            let chosen = manifest.protected
+                || manifest.pinned
+                || manifest.default
+                || config.keep.iter().any(|keep| *keep == dir_name);
+
+            let recent = config.keep_last.is_some_and(|keep_last| idx < keep_last);
+
+            let fresh = config
+                .keep_days
+                .is_some_and(|keep_days| now.saturating_sub(manifest.installed) < keep_days * DAY);
+
+            if !(chosen || recent || fresh) {
+                removed.push((path.clone(), manifest.size));
+            }
+        }
+    }
+
+    removed.sort();
+    Ok(removed)
+}
+
+/// Removes the installs [`plan`] selects, or only lists them when `dry_run` is set.
+pub fn prune(config: &Config, dry_run: bool) -> Result<PruneReport, String> {
+    let mut report = PruneReport {
+        dry_run,
+        ..Default::default()
+    };
+
+    for (path, size) in plan(config)? {
+        if !dry_run {
+            remove_install(&path, |_, _| {})?;
+        }
+
+        report.freed += size;
+        report.removed.push(path);
+    }
+
+    if !dry_run && !report.removed.is_empty() {
+        update_links(config)?;
+        update_entries(config)?;
+    }
+
+    Ok(report)
+}
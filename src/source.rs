@@ -0,0 +1,84 @@
+use serde::Deserialize;
+
+/// Where a listing of builds comes from, set with `[[sources]]` in config.toml.
+#[derive(Debug, Deserialize, Clone)]
+pub struct Source {
+    pub kind: SourceKind,
+    /// Name shown in the source column, the kind when unset.
+    pub name: Option<String>,
+    /// Listing url, e.g. a mirror, the kind's default when unset.
+    pub link: Option<String>,
+    /// Pattern mapping asset names of a `feed` source to builds, see
+    /// [`parse_feed`](crate::blender_utils::parse_feed).
+    pub pattern: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceKind {
+    /// builder.blender.org daily builds.
+    Daily,
+    /// Older daily builds moved out of the daily page.
+    Archive,
+    /// Builds of experimental branches.
+    Experimental,
+    /// Builds of pull requests.
+    Patch,
+    /// Official releases from download.blender.org.
+    Release,
+    /// GitHub-style release feed of a fork such as UPBGE or Bforartists.
+    Feed,
+}
+
+impl SourceKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            SourceKind::Daily => "daily",
+            SourceKind::Archive => "archive",
+            SourceKind::Experimental => "experimental",
+            SourceKind::Patch => "patch",
+            SourceKind::Release => "release",
+            SourceKind::Feed => "feed",
+        }
+    }
+
+    /// Whether `update` follows the builds of this kind, which excludes feature branches,
+    /// pull requests and forks.
+    pub fn is_tracked(self) -> bool {
+        matches!(
+            self,
+            SourceKind::Daily | SourceKind::Archive | SourceKind::Release
+        )
+    }
+
+    pub fn default_link(self) -> &'static str {
+        match self {
+            SourceKind::Daily => "https://builder.blender.org/download/daily/",
+            SourceKind::Archive => "https://builder.blender.org/download/daily/archive/",
+            SourceKind::Experimental => "https://builder.blender.org/download/experimental/",
+            SourceKind::Patch => "https://builder.blender.org/download/patch/",
+            SourceKind::Release => "https://download.blender.org/release/",
+            // feeds have no default, `link` is required
+            SourceKind::Feed => "",
+        }
+    }
+}
+
+impl Source {
+    pub fn new(kind: SourceKind) -> Self {
+        Source {
+            kind,
+            name: None,
+            link: None,
+            pattern: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or(self.kind.name())
+    }
+
+    pub fn link(&self) -> &str {
+        self.link.as_deref().unwrap_or(self.kind.default_link())
+    }
+}
@@ -1,32 +1,82 @@
-use std::time::Instant;
+use std::{fmt::Display, time::Instant};
+
+/// Snapshot of a transfer, sent to the TUI and CLI as a job progresses.
+#[derive(Debug, Clone)]
+pub struct Progress {
+    pub total_read: usize,
+    pub content_length: usize,
+
+    pub speed: String,
+    pub elapsed: String,
+    pub estimated: String,
+}
+
+impl Progress {
+    pub fn ratio(&self) -> f64 {
+        if self.content_length == 0 {
+            return 0.0;
+        }
+        (self.total_read as f64 / self.content_length as f64).clamp(0.0, 1.0)
+    }
+
+    fn progress_bar(&self) -> String {
+        let bar_length = 15.0;
+        let percentage = self.ratio() * 100.0;
+
+        let current_index = ((percentage / 100.0) * bar_length).floor() as usize;
+
+        let mut bar = "".to_owned();
+        for idx in 0..bar_length as usize {
+            if idx < current_index {
+                bar.push('⣿');
+            } else {
+                bar.push('·');
+            }
+        }
+
+        format!("{:>5.1}%  [{}] {}", percentage, bar, current_index)
+    }
+}
+
+impl Display for Progress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} | {} | {} | estimated {}",
+            self.progress_bar(),
+            self.speed,
+            self.elapsed,
+            self.estimated
+        )
+    }
+}
 
 pub struct ProgressTracker {
     content_length: usize,
 
     pub total_read: usize,
+    /// Bytes that were already on disk when tracking started.
+    resumed_from: usize,
     incremental_read: usize,
 
-    percentage: f32,
     start: Instant,
     timer: Instant,
 }
 
 impl ProgressTracker {
-    pub fn new(content_length: usize) -> Self {
+    pub fn new(content_length: usize, resumed_from: usize) -> Self {
         ProgressTracker {
             content_length,
-            total_read: 0,
+            total_read: resumed_from,
+            resumed_from,
             incremental_read: 0,
-            percentage: 0.0,
             start: Instant::now(),
             timer: Instant::now(),
         }
     }
 
-    pub fn update(&mut self, read: usize) -> Option<String> {
+    pub fn update(&mut self, read: usize) -> Option<Progress> {
         self.total_read += read;
-        self.percentage = self.total_read as f32 / self.content_length as f32 * 100.0;
-
         self.incremental_read += read;
 
         let incremental_time = self.timer.elapsed().as_secs_f32();
@@ -34,20 +84,26 @@ impl ProgressTracker {
         if incremental_time > 1.0 {
             let kbs = self.incremental_read as f32 / incremental_time / 1000.0;
 
-            let s = self.display(kbs);
+            let progress = self.progress(kbs);
 
             self.timer = Instant::now();
             self.incremental_read = 0;
 
-            return Some(s);
+            return Some(progress);
         }
         None
     }
 
     fn estimated(&self) -> String {
-        let rate = self.total_read as u64 / self.start.elapsed().as_secs();
-        let remaining = self.content_length - self.total_read;
-        let estimated_total = remaining as u64 / rate;
+        let read = (self.total_read - self.resumed_from) as u64;
+        let remaining = self.content_length.saturating_sub(self.total_read) as u64;
+
+        let Some(estimated_total) = read
+            .checked_div(self.start.elapsed().as_secs())
+            .and_then(|rate| remaining.checked_div(rate))
+        else {
+            return "-".to_owned();
+        };
 
         let min = estimated_total / 60;
         let secs = estimated_total.rem_euclid(60);
@@ -76,39 +132,19 @@ impl ProgressTracker {
         }
     }
 
-    fn progress_bar(&self) -> String {
-        let bar_length = 15.0;
-
-        let current_index = ((self.percentage / 100.0) * bar_length).floor() as usize;
-
-        let mut bar = "".to_owned();
-        for idx in 0..bar_length as usize {
-            if idx < current_index {
-                bar.push('⣿');
-            } else {
-                bar.push('·');
-            }
+    fn progress(&self, kbs: f32) -> Progress {
+        Progress {
+            total_read: self.total_read,
+            content_length: self.content_length,
+            speed: ProgressTracker::kbs_to_human_readable(kbs),
+            elapsed: self.elapsed_to_human_readable(),
+            estimated: self.estimated(),
         }
-
-        format!("{:>5.1}%  [{}] {}", self.percentage, bar, current_index)
-    }
-
-    fn display(&self, kbs: f32) -> String {
-        let s = format!(
-            "{} | {} | {} | estimated {}",
-            self.progress_bar(),
-            ProgressTracker::kbs_to_human_readable(kbs),
-            self.elapsed_to_human_readable(),
-            self.estimated()
-        );
-
-        s
     }
 
-    #[allow(unused)]
-    pub fn flush(&self) {
+    pub fn flush(&self) -> Progress {
         let incremental_time = self.timer.elapsed().as_secs_f32();
         let kbs = self.incremental_read as f32 / incremental_time / 1000.0;
-        self.display(kbs);
+        self.progress(kbs)
     }
 }
@@ -1,5 +1,6 @@
 use std::{
     io::{self},
+    path::{Path, PathBuf},
     rc::Rc,
     sync::{Arc, RwLock},
     time::Duration,
@@ -17,16 +18,30 @@ use ratatui::{
     widgets::Widget,
 };
 
-use crate::config::Config;
+use crate::{
+    config::Config, desktop::update_entries, launcher::Launcher, links::update_links,
+    manager::DownloadManager,
+    prune::{has_retention, plan, prune},
+    LocalBlenderVersion,
+};
 
-use super::{utils::Tui, Message, TxMessage};
+use super::{utils::Tui, JobEvent, Message, TxMessage};
 
 mod widgets;
 
 use widgets::{
+    confirm::{ConfirmAction, ConfirmDialog},
     files::FileListWidget,
     help::HelpWidget,
-    remote::{extract_and_clean, get_file, get_links, RemoteWidget},
+    instances::InstancesWidget,
+    profiles::ProfilePicker,
+    queue::{JobState, QueueWidget},
+    remote::RemoteWidget,
+};
+
+pub use widgets::{
+    files::utils::{check_downloaded, parse_downloaded, remove_install},
+    remote::{extract_and_clean, get_file, get_links},
 };
 
 mod state;
@@ -34,20 +49,34 @@ use state::{ActiveWidget, State, StateRef};
 
 pub struct TuiApp {
     done: bool,
+    quitting: bool,
 
     state: StateRef,
 
     events_tx: TxMessage,
     events: Receiver<Message>,
 
+    manager: DownloadManager,
+    launcher: Launcher,
+
     file_widget: FileListWidget,
     help_widget: HelpWidget,
+    instances_widget: InstancesWidget,
+    queue_widget: QueueWidget,
     remote_widget: RemoteWidget,
+
+    confirm_dialog: Option<ConfirmDialog>,
+    profile_picker: Option<ProfilePicker>,
 }
 
 impl TuiApp {
     pub fn new(config: Config) -> Self {
-        let (tx, rx) = tokio::sync::mpsc::channel::<Message>(1);
+        // retries are reported with try_send from sync callbacks, leave room for them
+        let (tx, rx) = tokio::sync::mpsc::channel::<Message>(64);
+        let tx = Arc::new(tx);
+
+        let manager = DownloadManager::new(config.clone(), tx.clone());
+        let launcher = Launcher::new(PathBuf::from(&config.path), tx.clone());
 
         let state = Rc::new(RwLock::new(State {
             config,
@@ -55,19 +84,34 @@ impl TuiApp {
         }));
 
         let help_widget = HelpWidget::new();
-        let file_widget = FileListWidget::new(state.clone());
-        let remote_widget = RemoteWidget::new(state.clone());
+        let mut file_widget = FileListWidget::new(state.clone());
+        let mut remote_widget = RemoteWidget::new(state.clone());
+        if let Err(err) = file_widget.refresh_local() {
+            remote_widget.set_message(format!("could not read installs: {err}"));
+        }
+        remote_widget.set_local(file_widget.files().to_vec());
+        let queue_widget = QueueWidget::new(state.clone());
+        let instances_widget = InstancesWidget::new(state.clone());
 
         TuiApp {
             done: false,
+            quitting: false,
 
-            events_tx: Arc::new(tx),
+            events_tx: tx,
             events: rx,
 
+            manager,
+            launcher,
+
             file_widget,
             help_widget,
+            instances_widget,
+            queue_widget,
             remote_widget,
 
+            confirm_dialog: None,
+            profile_picker: None,
+
             state,
         }
     }
@@ -81,6 +125,7 @@ impl TuiApp {
         while !self.done {
             tokio::select! {
                 _ = interval.tick() => {
+                    self.instances_widget.refresh_log();
                     terminal.draw(|frame| {
                         self.render_frame(frame);
                     })?;
@@ -107,24 +152,60 @@ impl TuiApp {
             Message::VersionUpdate(s) => {
                 self.remote_widget.set_message(s);
             }
-            Message::VersionResult(path) => {
-                self.remote_widget.set_message("downloaded...extracting...");
+            Message::Instance(id, event) => {
+                self.instances_widget.update(id, event);
+            }
+            Message::Pruned(result) => {
+                self.file_widget.set_removing(None);
+                self.refresh_local();
+
+                match result {
+                    Ok(report) => self.remote_widget.set_message(report.summary()),
+                    Err(err) => self.remote_widget.set_message(err),
+                }
+            }
+            Message::RemoveProgress(removed, total) => {
+                self.file_widget.set_removing(Some((removed, total)));
+            }
+            Message::Removed(result) => {
+                self.file_widget.set_removing(None);
+                self.refresh_local();
+
+                let mut message = match result {
+                    Ok(path) => format!("removed {}", path.display()),
+                    Err(err) => err,
+                };
 
                 let config = self.state.read().unwrap().config.clone();
-                let handle = tokio::task::spawn_blocking(move || {
-                    extract_and_clean(path, &config);
-                });
-
-                let tx = self.events_tx.clone();
-                tokio::spawn(async move {
-                    handle.await.unwrap();
-                    tx.send(Message::ExtractResult).await.unwrap();
-                });
+                if let Err(err) = update_links(&config) {
+                    message.push_str(&format!(", could not update links: {err}"));
+                }
+                if let Err(err) = update_entries(&config) {
+                    message.push_str(&format!(", could not update desktop entries: {err}"));
+                }
+                self.remote_widget.set_message(message);
             }
+            Message::Job(id, event) => {
+                match &event {
+                    JobEvent::Done(_) => {
+                        self.remote_widget.set_message("ready");
+                        self.refresh_local();
+                    }
+                    JobEvent::Failed(err) => {
+                        self.remote_widget.set_message(err);
+                    }
+                    JobEvent::Retry(retry) => {
+                        let name = self.queue_widget.name(id).unwrap_or_default();
+                        self.remote_widget.set_message(format!("{name}: {retry}"));
+                    }
+                    _ => {}
+                }
+
+                self.queue_widget.update(id, event);
 
-            Message::ExtractResult => {
-                self.file_widget.refresh_local();
-                self.remote_widget.set_message("ready");
+                if self.quitting && !self.queue_widget.has_running() {
+                    self.done = true;
+                }
             }
         }
     }
@@ -132,6 +213,51 @@ impl TuiApp {
     fn handle_events(&mut self, event: Event) -> io::Result<()> {
         match event {
             Event::Key(key_event) if key_event.kind == KeyEventKind::Release => {}
+            Event::Key(key_event)
+                if key_event.kind == KeyEventKind::Press && self.confirm_dialog.is_some() =>
+            {
+                match key_event.code {
+                    KeyCode::Char('y') | KeyCode::Enter => {
+                        match self.confirm_dialog.take().map(|dialog| dialog.action) {
+                            Some(ConfirmAction::Remove(path)) => self.remove(path),
+                            Some(ConfirmAction::Prune) => self.prune(),
+                            None => {}
+                        }
+                    }
+                    KeyCode::Char('n') | KeyCode::Esc => self.confirm_dialog = None,
+                    _ => {}
+                }
+            }
+            Event::Key(key_event)
+                if key_event.kind == KeyEventKind::Press && self.profile_picker.is_some() =>
+            {
+                let picker = self.profile_picker.as_mut().unwrap();
+
+                match key_event.code {
+                    KeyCode::Up => picker.decrement_active_selection(),
+                    KeyCode::Down => picker.increment_active_selection(),
+                    KeyCode::Enter => {
+                        if let Some(picker) = self.profile_picker.take() {
+                            self.launch(&picker.local, picker.selected());
+                        }
+                    }
+                    KeyCode::Esc => self.profile_picker = None,
+                    _ => {}
+                }
+            }
+            Event::Key(key_event)
+                if key_event.kind == KeyEventKind::Press
+                    && self.instances_widget.log_view().is_some() =>
+            {
+                match key_event.code {
+                    KeyCode::Up => self.instances_widget.scroll_log(1),
+                    KeyCode::Down => self.instances_widget.scroll_log(-1),
+                    KeyCode::PageUp => self.instances_widget.scroll_log(10),
+                    KeyCode::PageDown => self.instances_widget.scroll_log(-10),
+                    KeyCode::Esc | KeyCode::Enter => self.instances_widget.close_log(),
+                    _ => {}
+                }
+            }
             Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
                 match key_event.code {
                     KeyCode::Up => match self.state.read().unwrap().active_widget {
@@ -141,6 +267,12 @@ impl TuiApp {
                         ActiveWidget::RemoteWidget => {
                             self.remote_widget.decrement_active_selection();
                         }
+                        ActiveWidget::QueueWidget => {
+                            self.queue_widget.decrement_active_selection();
+                        }
+                        ActiveWidget::InstancesWidget => {
+                            self.instances_widget.decrement_active_selection();
+                        }
                     },
                     KeyCode::Down => match self.state.read().unwrap().active_widget {
                         ActiveWidget::FileListWidget => {
@@ -149,6 +281,12 @@ impl TuiApp {
                         ActiveWidget::RemoteWidget => {
                             self.remote_widget.increment_active_selection();
                         }
+                        ActiveWidget::QueueWidget => {
+                            self.queue_widget.increment_active_selection();
+                        }
+                        ActiveWidget::InstancesWidget => {
+                            self.instances_widget.increment_active_selection();
+                        }
                     },
                     KeyCode::Left => {
                         let mut state = self.state.write().unwrap();
@@ -160,30 +298,163 @@ impl TuiApp {
 
                         state.active_widget = ActiveWidget::RemoteWidget;
                     }
+                    KeyCode::Tab => {
+                        let mut state = self.state.write().unwrap();
+
+                        state.active_widget = match state.active_widget {
+                            ActiveWidget::FileListWidget => ActiveWidget::RemoteWidget,
+                            ActiveWidget::RemoteWidget => ActiveWidget::QueueWidget,
+                            ActiveWidget::QueueWidget => ActiveWidget::InstancesWidget,
+                            ActiveWidget::InstancesWidget => ActiveWidget::FileListWidget,
+                        };
+                    }
                     KeyCode::Char(' ') => {}
+                    KeyCode::Char('p') => {
+                        let queue = matches!(
+                            self.state.read().unwrap().active_widget,
+                            ActiveWidget::QueueWidget
+                        );
+                        if queue {
+                            match self.queue_widget.selected() {
+                                Some((id, JobState::Paused)) => self.manager.resume(id),
+                                Some((id, state)) if state.is_running() => self.manager.pause(id),
+                                _ => {}
+                            }
+                        }
+                    }
+                    KeyCode::Char('d') => {
+                        let file_list = matches!(
+                            self.state.read().unwrap().active_widget,
+                            ActiveWidget::FileListWidget
+                        );
+                        if file_list {
+                            match self.file_widget.selected() {
+                                _ if self.file_widget.is_removing() => self
+                                    .remote_widget
+                                    .set_message("already removing an install"),
+                                Some(local) if local.protected => self
+                                    .remote_widget
+                                    .set_message(format!("{} is protected", local.path.display())),
+                                Some(local) => {
+                                    self.confirm_dialog = Some(ConfirmDialog::remove(local))
+                                }
+                                None => {}
+                            }
+                        }
+                    }
+                    KeyCode::Char('l') => {
+                        let file_list = matches!(
+                            self.state.read().unwrap().active_widget,
+                            ActiveWidget::FileListWidget
+                        );
+                        if file_list {
+                            self.toggle_protected();
+                        }
+                    }
+                    KeyCode::Char('x') => {
+                        let file_list = matches!(
+                            self.state.read().unwrap().active_widget,
+                            ActiveWidget::FileListWidget
+                        );
+                        if file_list {
+                            self.plan_prune();
+                        }
+                    }
+                    KeyCode::Char('s') => {
+                        let file_list = matches!(
+                            self.state.read().unwrap().active_widget,
+                            ActiveWidget::FileListWidget
+                        );
+                        if file_list {
+                            self.set_default();
+                        }
+                    }
+                    KeyCode::Char('u') => {
+                        let remote = matches!(
+                            self.state.read().unwrap().active_widget,
+                            ActiveWidget::RemoteWidget
+                        );
+                        if remote {
+                            let queued = self
+                                .remote_widget
+                                .outdated()
+                                .into_iter()
+                                .filter(|version| self.manager.enqueue(version.clone()).is_ok())
+                                .count();
+
+                            let message = match queued {
+                                0 => "nothing to update".to_owned(),
+                                len => format!("updating {len} builds"),
+                            };
+                            self.remote_widget.set_message(message);
+                        }
+                    }
+                    KeyCode::Char('f') => {
+                        let remote = matches!(
+                            self.state.read().unwrap().active_widget,
+                            ActiveWidget::RemoteWidget
+                        );
+                        if remote {
+                            self.remote_widget.cycle_filter();
+                        }
+                    }
+                    KeyCode::Char('k') => {
+                        let instances = matches!(
+                            self.state.read().unwrap().active_widget,
+                            ActiveWidget::InstancesWidget
+                        );
+                        if instances {
+                            if let Some(id) = self.instances_widget.selected_running() {
+                                self.launcher.kill(id);
+                            }
+                        }
+                    }
+                    KeyCode::Char('c') => {
+                        let queue = matches!(
+                            self.state.read().unwrap().active_widget,
+                            ActiveWidget::QueueWidget
+                        );
+                        if queue {
+                            if let Some((id, _)) = self.queue_widget.selected() {
+                                self.manager.cancel(id);
+                            }
+                        }
+                    }
                     KeyCode::Char('q') => {
-                        self.done = true;
+                        // let running jobs clean up before leaving
+                        self.quitting = true;
+                        self.manager.cancel_all();
+                        self.remote_widget.set_message("cancelling downloads...");
+                        self.done = !self.queue_widget.has_running();
                     }
                     KeyCode::Enter => {
                         let active_widget = &self.state.read().unwrap().active_widget;
                         match active_widget {
-                            ActiveWidget::FileListWidget => {}
+                            ActiveWidget::QueueWidget => {}
+                            ActiveWidget::FileListWidget => {
+                                let config = &self.state.read().unwrap().config;
+
+                                match self.file_widget.selected() {
+                                    Some(local) if config.profiles.is_empty() => {
+                                        if let Err(err) = self.launcher.launch(local, None) {
+                                            self.remote_widget.set_message(err);
+                                        }
+                                    }
+                                    Some(local) => {
+                                        self.profile_picker =
+                                            Some(ProfilePicker::new(local.clone(), config))
+                                    }
+                                    None => {}
+                                }
+                            }
+                            ActiveWidget::InstancesWidget => self.instances_widget.open_log(),
                             ActiveWidget::RemoteWidget => {
                                 if self.remote_widget.select_mode {
-                                    let version = self.remote_widget.download_selected();
-                                    let config = self.state.read().unwrap().config.clone();
-                                    let tx = self.events_tx.clone();
-
-                                    tokio::spawn(async move {
-                                        let (mut file, path) = get_file(&version, config);
-                                        crate::getter::download_with_tx(
-                                            &version.link,
-                                            &mut file,
-                                            path,
-                                            tx,
-                                        )
-                                        .await;
-                                    });
+                                    if let Some(version) = self.remote_widget.download_selected() {
+                                        if let Err(err) = self.manager.enqueue(version) {
+                                            self.remote_widget.set_message(err);
+                                        }
+                                    }
                                 } else {
                                     self.remote_widget
                                         .set_message("checking available versions...");
@@ -192,12 +463,21 @@ impl TuiApp {
                                     let tx = self.events_tx.clone();
 
                                     tokio::spawn(async move {
-                                        let versions = get_links(config).await;
+                                        let versions = get_links(config, tx.clone()).await;
                                         match versions {
-                                            Ok(versions) => {
-                                                tx.send(Message::Links(versions))
+                                            Ok(listing) => {
+                                                tx.send(Message::Links(listing.versions))
+                                                    .await
+                                                    .unwrap();
+
+                                                // the sources that worked are still listed
+                                                if !listing.failed.is_empty() {
+                                                    tx.send(Message::Error(
+                                                        listing.failed.join("; "),
+                                                    ))
                                                     .await
                                                     .unwrap();
+                                                }
                                             }
                                             Err(err) => {
                                                 tx.send(Message::Error(err.to_string()))
@@ -218,6 +498,113 @@ impl TuiApp {
         Ok(())
     }
 
+    /// Reads the installs again and compares the remote builds against them.
+    fn refresh_local(&mut self) {
+        if let Err(err) = self.file_widget.refresh_local() {
+            self.remote_widget
+                .set_message(format!("could not read installs: {err}"));
+        }
+        self.remote_widget
+            .set_local(self.file_widget.files().to_vec());
+    }
+
+    /// Deletes the install at `path` in the background, reporting progress as messages.
+    fn remove(&mut self, path: PathBuf) {
+        self.file_widget.set_removing(Some((0, 0)));
+
+        let tx = self.events_tx.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let result = remove_install(&path, |removed, total| {
+                let _ = tx.blocking_send(Message::RemoveProgress(removed, total));
+            });
+            let _ = tx.blocking_send(Message::Removed(result.map(|()| path)));
+        });
+    }
+
+    fn launch(&mut self, local: &LocalBlenderVersion, profile: Option<&str>) {
+        let config = self.state.read().unwrap().config.clone();
+        let profile = profile.and_then(|name| config.profiles.get(name));
+
+        if let Err(err) = self.launcher.launch(local, profile) {
+            self.remote_widget.set_message(err);
+        }
+    }
+
+    /// Shows what a prune would remove and asks to go ahead.
+    fn plan_prune(&mut self) {
+        let config = self.state.read().unwrap().config.clone();
+
+        if !has_retention(&config) {
+            self.remote_widget
+                .set_message("no retention settings, set `keep_last` or `keep_days`");
+            return;
+        }
+
+        match plan(&config) {
+            Ok(plan) if plan.is_empty() => self.remote_widget.set_message("nothing to prune"),
+            Ok(plan) => self.confirm_dialog = Some(ConfirmDialog::prune(&plan)),
+            Err(err) => self.remote_widget.set_message(err),
+        }
+    }
+
+    fn prune(&mut self) {
+        self.file_widget.set_removing(Some((0, 0)));
+
+        let config = self.state.read().unwrap().config.clone();
+        let tx = self.events_tx.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let _ = tx.blocking_send(Message::Pruned(prune(&config, false)));
+        });
+    }
+
+    /// Points `blender-current` and the series link at the selected install.
+    fn set_default(&mut self) {
+        let Some(local) = self.file_widget.selected() else {
+            return;
+        };
+
+        let config = self.state.read().unwrap().config.clone();
+        let dir_name = local
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let result = crate::manifest::set_default(Path::new(&config.path), &dir_name)
+            .and_then(|()| update_links(&config))
+            .and_then(|()| update_entries(&config));
+
+        match result {
+            Ok(()) => {
+                self.remote_widget
+                    .set_message(format!("{dir_name} is now the default"));
+                self.refresh_local();
+            }
+            Err(err) => self.remote_widget.set_message(err),
+        }
+    }
+
+    fn toggle_protected(&mut self) {
+        let Some(local) = self.file_widget.selected() else {
+            return;
+        };
+
+        let base = PathBuf::from(&self.state.read().unwrap().config.path);
+        let dir_name = local
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let protected = !local.protected;
+
+        match crate::manifest::update(&base, &dir_name, |manifest| manifest.protected = protected) {
+            Ok(()) => self.refresh_local(),
+            Err(err) => self.remote_widget.set_message(err),
+        }
+    }
+
     fn render_frame(&self, frame: &mut ratatui::Frame) {
         frame.render_widget(self, frame.area());
     }
@@ -238,8 +625,38 @@ impl Widget for &TuiApp {
             .constraints([Constraint::Percentage(40), Constraint::default()])
             .split(main_layout[0]);
 
-        self.file_widget.render(split_layout[0], buf);
-        self.remote_widget.render(split_layout[1], buf);
+        let local_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Fill(1),
+                Constraint::Length(self.instances_widget.height()),
+            ])
+            .split(split_layout[0]);
+
+        let remote_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Fill(1),
+                Constraint::Length(self.queue_widget.height()),
+            ])
+            .split(split_layout[1]);
+
+        self.file_widget.render(local_layout[0], buf);
+        self.instances_widget.render(local_layout[1], buf);
+        self.remote_widget.render(remote_layout[0], buf);
+        self.queue_widget.render(remote_layout[1], buf);
         self.help_widget.render(main_layout[1], buf);
+
+        if let Some(log_view) = self.instances_widget.log_view() {
+            log_view.render(area, buf);
+        }
+
+        if let Some(dialog) = &self.confirm_dialog {
+            dialog.render(area, buf);
+        }
+
+        if let Some(picker) = &self.profile_picker {
+            picker.render(area, buf);
+        }
     }
 }
@@ -2,9 +2,12 @@ use std::{rc::Rc, sync::RwLock};
 
 use crate::config::Config;
 
+#[allow(clippy::enum_variant_names)]
 pub enum ActiveWidget {
     FileListWidget,
     RemoteWidget,
+    QueueWidget,
+    InstancesWidget,
 }
 
 pub struct State {
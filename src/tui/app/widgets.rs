@@ -1,6 +1,10 @@
+pub mod confirm;
 pub mod files;
 pub mod remote;
 pub mod help;
+pub mod profiles;
+pub mod instances;
+pub mod queue;
 
 use super::StateRef;
 use super::ActiveWidget;
\ No newline at end of file
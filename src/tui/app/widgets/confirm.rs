@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+
+use ratatui::{
+    layout::{Constraint, Flex, Layout},
+    prelude::{Buffer, Rect, Stylize},
+    symbols::border,
+    text::{Line, Text},
+    widgets::{Block, Clear, Padding, Paragraph, Widget},
+};
+
+use crate::LocalBlenderVersion;
+
+/// Most lines listed in the dialog, the rest are summarized.
+const MAX_LINES: usize = 12;
+
+/// What happens when the dialog is confirmed.
+pub enum ConfirmAction {
+    Remove(PathBuf),
+    Prune,
+}
+
+/// Asks for confirmation before installs are deleted.
+pub struct ConfirmDialog {
+    pub action: ConfirmAction,
+    title: String,
+    lines: Vec<String>,
+}
+
+fn dir_name(path: &std::path::Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+impl ConfirmDialog {
+    pub fn remove(local: &LocalBlenderVersion) -> Self {
+        let size = local.blender_version.size.unwrap_or_default();
+
+        ConfirmDialog {
+            action: ConfirmAction::Remove(local.path.clone()),
+            title: " remove ".to_owned(),
+            lines: vec![format!(
+                "remove {} ({:.1}mb)?",
+                dir_name(&local.path),
+                size as f32 / 1000000.0
+            )],
+        }
+    }
+
+    /// Lists the installs a prune would remove, as planned by a dry run.
+    pub fn prune(plan: &[(PathBuf, u64)]) -> Self {
+        let freed: u64 = plan.iter().map(|(_, size)| size).sum();
+
+        let mut lines: Vec<String> = plan
+            .iter()
+            .take(MAX_LINES)
+            .map(|(path, _)| dir_name(path))
+            .collect();
+        if plan.len() > MAX_LINES {
+            lines.push(format!("and {} more", plan.len() - MAX_LINES));
+        }
+        lines.push(format!(
+            "remove {} builds, freeing {:.1}mb?",
+            plan.len(),
+            freed as f32 / 1000000.0
+        ));
+
+        ConfirmDialog {
+            action: ConfirmAction::Prune,
+            title: " prune ".to_owned(),
+            lines,
+        }
+    }
+}
+
+impl Widget for &ConfirmDialog {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [area] = Layout::vertical([Constraint::Length(self.lines.len() as u16 + 5)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::horizontal([Constraint::Percentage(60)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        let block = Block::bordered()
+            .title(self.title.clone())
+            .border_set(border::ROUNDED)
+            .padding(Padding::uniform(1))
+            .red();
+
+        let mut lines: Vec<Line> = self
+            .lines
+            .iter()
+            .map(|line| Line::from(line.as_str()))
+            .collect();
+        lines.push(Line::from("y confirm | n cancel"));
+
+        Clear.render(area, buf);
+        Paragraph::new(Text::from(lines))
+            .centered()
+            .block(block)
+            .render(area, buf);
+    }
+}
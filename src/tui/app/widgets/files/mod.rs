@@ -1,4 +1,4 @@
-use crate::LocalBlenderVersion;
+use crate::{LocalBlenderVersion, Release};
 use ratatui::{
     layout::Alignment,
     prelude::{Buffer, Rect, Stylize},
@@ -10,7 +10,7 @@ use ratatui::{
 
 use super::StateRef;
 
-mod utils;
+pub mod utils;
 
 pub struct FileListWidget {
     state: StateRef,
@@ -18,30 +18,52 @@ pub struct FileListWidget {
     files: Vec<LocalBlenderVersion>,
     selected: usize,
     len: usize,
+
+    /// Removed and total bytes of the install being deleted.
+    removing: Option<(u64, u64)>,
 }
 
 impl FileListWidget {
+    /// An empty list, filled by [`refresh_local`](Self::refresh_local).
     pub fn new(state: StateRef) -> Self {
-        let mut file_list_widget = FileListWidget {
+        FileListWidget {
             state,
 
             len: 0,
             files: Vec::new(),
             selected: 0,
-        };
 
-        file_list_widget.refresh_local();
-        file_list_widget
+            removing: None,
+        }
     }
 
-    pub fn refresh_local(&mut self) {
+    /// Reads the installs again, keeping the current list when that fails.
+    pub fn refresh_local(&mut self) -> Result<(), String> {
         let config = self.state.read().unwrap().config.clone();
 
-        let file_list = utils::check_downloaded(&config).unwrap();
+        let file_list = utils::check_downloaded(&config)?;
         let files = utils::parse_downloaded(file_list);
 
         self.len = files.len();
         self.files = files;
+        self.selected = self.selected.min(self.len.saturating_sub(1));
+        Ok(())
+    }
+
+    pub fn files(&self) -> &[LocalBlenderVersion] {
+        &self.files
+    }
+
+    pub fn selected(&self) -> Option<&LocalBlenderVersion> {
+        self.files.get(self.selected)
+    }
+
+    pub fn set_removing(&mut self, removing: Option<(u64, u64)>) {
+        self.removing = removing;
+    }
+
+    pub fn is_removing(&self) -> bool {
+        self.removing.is_some()
     }
 }
 
@@ -55,6 +77,10 @@ impl FileListWidget {
     }
 
     pub fn decrement_active_selection(&mut self) {
+        if self.len == 0 {
+            return;
+        }
+
         if self.selected == 0 {
             self.selected = self.len - 1;
         } else {
@@ -72,11 +98,19 @@ impl Widget for &FileListWidget {
             .border_set(border::ROUNDED)
             .padding(Padding::uniform(1));
 
+        if let Some((removed, total)) = self.removing {
+            let percentage = match total {
+                0 => 100.0,
+                total => removed as f64 / total as f64 * 100.0,
+            };
+            block = block.title_bottom(format!(" removing {percentage:.0}% "));
+        }
+
         match self.state.read().unwrap().active_widget {
             super::ActiveWidget::FileListWidget => {
                 block = block.magenta();
             }
-            super::ActiveWidget::RemoteWidget => {
+            _ => {
                 block = block.cyan();
             }
         }
@@ -86,35 +120,45 @@ impl Widget for &FileListWidget {
             .iter()
             .enumerate()
             .map(|(idx, local)| {
-                let version_span = match &local.blender_version.version {
-                    x if x.contains("4.2") => {
-                        Span::styled(format!("{x}"), Style::default().bg(Color::Green))
-                    }
-                    x if x.contains("4.3") => {
-                        Span::styled(format!("{x}"), Style::default().bg(Color::Magenta))
-                    }
-                    x => Span::styled(format!("{x}"), Style::default().bg(Color::Gray)),
+                let x = local.blender_version.version.to_string();
+                let version_span = match local.blender_version.version.series().as_str() {
+                    "4.2" => Span::styled(x, Style::default().bg(Color::Green)),
+                    "4.3" => Span::styled(x, Style::default().bg(Color::Magenta)),
+                    _ => Span::styled(x, Style::default().bg(Color::Gray)),
                 };
 
-                let release_span = match local.blender_version.release.as_str() {
-                    x if x == "stable" => {
+                let x = local.blender_version.release;
+                let release_span = match x {
+                    Release::Stable => {
                         Span::styled(format!("{x:^8}"), Style::default().fg(Color::Green))
                     }
-                    x if x == "beta" => {
+                    Release::Rc | Release::Candidate => {
+                        Span::styled(format!("{x:^8}"), Style::default().fg(Color::Yellow))
+                    }
+                    Release::Beta => {
                         Span::styled(format!("{x:^8}"), Style::default().fg(Color::Magenta))
                     }
-                    x if x == "alpha" => {
+                    Release::Alpha => {
                         Span::styled(format!("{x:^8}"), Style::default().fg(Color::Gray))
                     }
-                    _ => Span::styled(String::new(), Style::default().fg(Color::Red)),
                 };
 
                 let branch_span = Span::raw(&local.blender_version.branch);
 
                 let created_span = Span::raw(format!(" {} ", &local.created));
 
-                let mut line =
-                    Line::from(vec![version_span, release_span, branch_span, created_span]);
+                let mut spans = vec![version_span, release_span, branch_span, created_span];
+                if local.default {
+                    spans.push(Span::styled("default ", Style::default().fg(Color::Green)));
+                }
+                if local.protected {
+                    spans.push(Span::styled(
+                        "protected",
+                        Style::default().fg(Color::Yellow),
+                    ));
+                }
+
+                let mut line = Line::from(spans);
 
                 if idx == self.selected {
                     line = line
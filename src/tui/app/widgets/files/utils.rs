@@ -1,24 +1,49 @@
-use std::{path::PathBuf, time::Duration};
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
-use crate::{config::Config, LocalBlenderVersion};
+use crate::{config::Config, manifest::Manifest, LocalBlenderVersion};
 
-pub(super) fn check_downloaded(config: &Config) -> Result<Vec<(PathBuf, Duration)>, String> {
-    let path = PathBuf::from(config.path.clone());
+/// Lists the installs in `config.path` with their manifests, rebuilding missing ones.
+pub fn check_downloaded(config: &Config) -> Result<Vec<(PathBuf, Manifest)>, String> {
+    crate::manifest::sync(Path::new(&config.path))
+}
 
-    let mut result = Vec::with_capacity(10);
+/// Deletes the install at `path`, calling `report` with the removed and total bytes a few
+/// times a second.
+pub fn remove_install(path: &Path, mut report: impl FnMut(u64, u64)) -> Result<(), String> {
+    let total = crate::manifest::dir_size(path);
+    let mut removed = 0;
+    let mut timer = Instant::now();
+
+    remove_tree(path, &mut |size| {
+        removed += size;
+        if timer.elapsed() > Duration::from_millis(100) {
+            report(removed, total);
+            timer = Instant::now();
+        }
+    })
+    .map_err(|err| format!("could not remove {}: {err}", path.display()))?;
 
-    for dir in std::fs::read_dir(path).unwrap() {
-        let dir = dir.unwrap();
+    report(total, total);
+    Ok(())
+}
 
-        let metadata = dir.metadata().unwrap();
+fn remove_tree(path: &Path, removed: &mut impl FnMut(u64)) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
 
         if metadata.is_dir() {
-            let elapsed = metadata.created().unwrap().elapsed().unwrap();
-            result.push((dir.path(), elapsed));
+            remove_tree(&entry.path(), removed)?;
+        } else {
+            std::fs::remove_file(entry.path())?;
+            removed(metadata.len());
         }
     }
 
-    Ok(result)
+    std::fs::remove_dir(path)
 }
 
 fn duration_to_human_readable(duration: Duration) -> String {
@@ -81,29 +106,25 @@ fn duration_to_human_readable(duration: Duration) -> String {
     }
 }
 
-pub(super) fn parse_downloaded(downloaded: Vec<(PathBuf, Duration)>) -> Vec<LocalBlenderVersion> {
-    let matcher = crate::blender_utils::BlenderMatcher::new();
+pub fn parse_downloaded(downloaded: Vec<(PathBuf, Manifest)>) -> Vec<LocalBlenderVersion> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
 
-    let result: Vec<LocalBlenderVersion> = downloaded
+    downloaded
         .into_iter()
-        .map(|(path, duration)| {
-            let Some(dir_name) = path.components().last() else {
-                return None;
-            };
-
-            let dir_name = dir_name.as_os_str().to_str().unwrap();
-
-            if let Some(version) = matcher.match_str(dir_name) {
-                return Some(LocalBlenderVersion {
-                    blender_version: version,
-                    created: duration_to_human_readable(duration),
-                });
+        .map(|(path, manifest)| {
+            let age = Duration::from_secs(now.saturating_sub(manifest.installed));
+
+            LocalBlenderVersion {
+                blender_version: manifest.blender_version(),
+                created: duration_to_human_readable(age),
+                installed: manifest.installed,
+                path,
+                protected: manifest.protected,
+                default: manifest.default,
             }
-            None
         })
-        .flatten()
-        .collect();
-
-    result
+        .collect()
 }
-
@@ -11,7 +11,8 @@ pub struct HelpWidget {
 impl HelpWidget {
     pub fn new() -> Self {
         HelpWidget {
-            message: "help".to_owned(),
+            message: "←/→ switch panel | tab next panel | enter select/launch/log | p pause/resume | c cancel | d remove | x prune | l protect | s default | u update all | f platform | k kill | q quit"
+                .to_owned(),
         }
     }
 }
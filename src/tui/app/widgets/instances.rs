@@ -0,0 +1,372 @@
+use std::{path::PathBuf, time::Instant};
+
+use ratatui::{
+    layout::{Constraint, Flex, Layout},
+    prelude::{Buffer, Rect, Stylize},
+    style::{Color, Style},
+    symbols::border,
+    text::{Line, Span, Text},
+    widgets::{Block, Clear, Padding, Paragraph, Widget},
+};
+
+use crate::{
+    launcher::read_log,
+    tui::{InstanceEvent, InstanceId},
+    BlenderVersion,
+};
+
+use super::{ActiveWidget, StateRef};
+
+/// Most lines kept in the log view.
+const MAX_LOG_LINES: usize = 5000;
+
+/// Most bytes of a log read at once, when it is opened or grew a lot since the last read.
+const MAX_LOG_READ: u64 = 1 << 20;
+
+/// Most instances shown at once, older ones scroll out of view.
+const MAX_VISIBLE: usize = 6;
+
+pub struct Instance {
+    id: InstanceId,
+    pid: u32,
+    version: BlenderVersion,
+    started: Instant,
+    log: PathBuf,
+    exited: Option<String>,
+}
+
+pub struct LogView {
+    title: String,
+    path: PathBuf,
+    lines: Vec<String>,
+    /// Bytes of the log read so far.
+    offset: u64,
+    /// The last line, still without its newline, is shown as the last of `lines`.
+    partial: Option<Vec<u8>>,
+    /// Lines scrolled up from the bottom.
+    scroll: usize,
+}
+
+impl LogView {
+    /// Appends what was written to the log since the last read.
+    fn read_new(&mut self) {
+        let (start, mut bytes) = read_log(&self.path, self.offset, MAX_LOG_READ);
+
+        if start != self.offset {
+            // output was skipped or the log started over, the old lines don't lead up to it
+            self.lines.clear();
+            self.partial = None;
+            if start > 0 {
+                let line_start = bytes
+                    .iter()
+                    .position(|byte| *byte == b'\n')
+                    .map_or(0, |idx| idx + 1);
+                bytes.drain(..line_start);
+            }
+        }
+        self.offset = start + bytes.len() as u64;
+
+        if bytes.is_empty() {
+            return;
+        }
+
+        let mut text = match self.partial.take() {
+            Some(partial) => {
+                self.lines.pop();
+                partial
+            }
+            None => Vec::new(),
+        };
+        text.extend_from_slice(&bytes);
+
+        let mut lines: Vec<&[u8]> = text.split(|byte| *byte == b'\n').collect();
+        let last = lines.pop().unwrap_or_default();
+
+        for line in lines
+            .iter()
+            .copied()
+            .chain((!last.is_empty()).then_some(last))
+        {
+            let line = String::from_utf8_lossy(line);
+            self.lines.push(line.trim_end_matches('\r').to_owned());
+        }
+        if !last.is_empty() {
+            self.partial = Some(last.to_vec());
+        }
+
+        let excess = self.lines.len().saturating_sub(MAX_LOG_LINES);
+        self.lines.drain(..excess);
+        self.scroll = self.scroll.min(self.lines.len());
+    }
+}
+
+pub struct InstancesWidget {
+    state: StateRef,
+
+    instances: Vec<Instance>,
+    selected: usize,
+
+    log_view: Option<LogView>,
+}
+
+impl InstancesWidget {
+    pub fn new(state: StateRef) -> Self {
+        InstancesWidget {
+            state,
+
+            instances: Vec::new(),
+            selected: 0,
+
+            log_view: None,
+        }
+    }
+
+    pub fn update(&mut self, id: InstanceId, event: InstanceEvent) {
+        match event {
+            InstanceEvent::Started { pid, version, log } => self.instances.push(Instance {
+                id,
+                pid,
+                version: *version,
+                started: Instant::now(),
+                log,
+                exited: None,
+            }),
+            InstanceEvent::Exited(status) => {
+                if let Some(instance) = self.instances.iter_mut().find(|instance| instance.id == id)
+                {
+                    instance.exited = Some(status);
+                }
+            }
+        }
+    }
+
+    /// Id of the selected instance, if it is still running.
+    pub fn selected_running(&self) -> Option<InstanceId> {
+        self.visible()
+            .get(self.selected)
+            .filter(|instance| instance.exited.is_none())
+            .map(|instance| instance.id)
+    }
+
+    pub fn increment_active_selection(&mut self) {
+        self.selected += 1;
+
+        if self.selected >= self.visible().len() {
+            self.selected = 0;
+        }
+    }
+
+    pub fn decrement_active_selection(&mut self) {
+        let len = self.visible().len();
+        if len == 0 {
+            return;
+        }
+
+        if self.selected == 0 {
+            self.selected = len - 1;
+        } else {
+            self.selected = self.selected.saturating_sub(1);
+        }
+    }
+
+    /// Rows needed to show every instance, including the border.
+    pub fn height(&self) -> u16 {
+        if self.instances.is_empty() {
+            return 0;
+        }
+        self.instances.len().min(MAX_VISIBLE) as u16 + 2
+    }
+
+    fn visible(&self) -> &[Instance] {
+        &self.instances[self.instances.len().saturating_sub(MAX_VISIBLE)..]
+    }
+
+    pub fn open_log(&mut self) {
+        let Some(instance) = self.visible().get(self.selected) else {
+            return;
+        };
+
+        self.log_view = Some(LogView {
+            title: format!(" log {} ({}) ", instance.version.version, instance.pid),
+            path: instance.log.clone(),
+            lines: Vec::new(),
+            offset: 0,
+            partial: None,
+            scroll: 0,
+        });
+        self.refresh_log();
+    }
+
+    pub fn close_log(&mut self) {
+        self.log_view = None;
+    }
+
+    pub fn log_view(&self) -> Option<&LogView> {
+        self.log_view.as_ref()
+    }
+
+    /// Reads new output of the instance whose log is open.
+    pub fn refresh_log(&mut self) {
+        if let Some(log_view) = &mut self.log_view {
+            log_view.read_new();
+        }
+    }
+
+    /// Scrolls the log up by `lines`, or down when negative.
+    pub fn scroll_log(&mut self, lines: isize) {
+        if let Some(log_view) = &mut self.log_view {
+            log_view.scroll = log_view
+                .scroll
+                .saturating_add_signed(lines)
+                .min(log_view.lines.len());
+        }
+    }
+}
+
+fn format_uptime(started: Instant) -> String {
+    let secs = started.elapsed().as_secs();
+    format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+impl Widget for &InstancesWidget {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut block = Block::bordered()
+            .title(" instances ")
+            .border_set(border::ROUNDED)
+            .padding(Padding::horizontal(1));
+
+        match self.state.read().unwrap().active_widget {
+            ActiveWidget::InstancesWidget => {
+                block = block.magenta();
+            }
+            _ => {
+                block = block.cyan();
+            }
+        }
+
+        let lines: Vec<Line> = self
+            .visible()
+            .iter()
+            .enumerate()
+            .map(|(idx, instance)| {
+                let status = match &instance.exited {
+                    Some(status) => Span::styled(status.clone(), Style::default().fg(Color::Red)),
+                    None => Span::styled(
+                        format_uptime(instance.started),
+                        Style::default().fg(Color::Green),
+                    ),
+                };
+
+                let mut line = Line::from(vec![
+                    Span::raw(format!("{:<8}", instance.pid)),
+                    Span::raw(format!(
+                        "{} {} {} ",
+                        instance.version.version, instance.version.release, instance.version.branch
+                    )),
+                    status,
+                ]);
+
+                if idx == self.selected {
+                    line = line.patch_style(Style::default().bg(Color::DarkGray));
+                }
+
+                line
+            })
+            .collect();
+
+        Paragraph::new(Text::from(lines))
+            .block(block)
+            .render(area, buf);
+    }
+}
+
+impl Widget for &LogView {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [area] = Layout::vertical([Constraint::Percentage(80)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::horizontal([Constraint::Percentage(90)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        let block = Block::bordered()
+            .title(self.title.clone())
+            .title_bottom(" ↑/↓ pgup/pgdn scroll | esc close ")
+            .border_set(border::ROUNDED)
+            .padding(Padding::horizontal(1))
+            .magenta();
+
+        let height = block.inner(area).height as usize;
+        let end = self.lines.len().saturating_sub(self.scroll);
+        let start = end.saturating_sub(height);
+
+        let lines: Vec<Line> = self.lines[start..end]
+            .iter()
+            .map(|line| Line::raw(line.as_str()))
+            .collect();
+
+        Clear.render(area, buf);
+        Paragraph::new(Text::from(lines))
+            .block(block)
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    fn log_view(path: PathBuf) -> LogView {
+        LogView {
+            title: String::new(),
+            path,
+            lines: Vec::new(),
+            offset: 0,
+            partial: None,
+            scroll: 0,
+        }
+    }
+
+    #[test]
+    fn log_is_read_incrementally() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blender.log");
+        std::fs::write(&path, "Blender 4.2.3\r\nRead prefs: ").unwrap();
+
+        let mut view = log_view(path.clone());
+        view.read_new();
+        assert_eq!(view.lines, ["Blender 4.2.3", "Read prefs: "]);
+
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        file.write_all(b"userpref.blend\nFra:1\n").unwrap();
+        view.read_new();
+        assert_eq!(
+            view.lines,
+            ["Blender 4.2.3", "Read prefs: userpref.blend", "Fra:1"]
+        );
+        assert_eq!(view.offset, std::fs::metadata(&path).unwrap().len());
+
+        // nothing new, nothing changes
+        view.read_new();
+        assert_eq!(view.lines.len(), 3);
+    }
+
+    #[test]
+    fn truncated_log_is_read_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blender.log");
+        std::fs::write(&path, "first run\nmore output\n").unwrap();
+
+        let mut view = log_view(path.clone());
+        view.read_new();
+
+        std::fs::write(&path, "second\n").unwrap();
+        view.read_new();
+        assert_eq!(view.lines, ["second"]);
+    }
+}
@@ -0,0 +1,92 @@
+use ratatui::{
+    layout::{Constraint, Flex, Layout},
+    prelude::{Buffer, Rect, Stylize},
+    style::{Color, Style},
+    symbols::border,
+    text::{Line, Text},
+    widgets::{Block, Clear, Padding, Paragraph, Widget},
+};
+
+use crate::{config::Config, LocalBlenderVersion};
+
+/// Picks the launch profile an install is started with.
+pub struct ProfilePicker {
+    pub local: LocalBlenderVersion,
+    /// Profile names, `None` launches without a profile.
+    options: Vec<Option<String>>,
+    selected: usize,
+}
+
+impl ProfilePicker {
+    /// Lists the profiles in `config`, preselecting the install's default one.
+    pub fn new(local: LocalBlenderVersion, config: &Config) -> Self {
+        let options: Vec<Option<String>> = std::iter::once(None)
+            .chain(config.profiles.keys().cloned().map(Some))
+            .collect();
+
+        let dir_name = local.path.file_name().unwrap_or_default().to_string_lossy();
+        let default = config.default_profile(&dir_name);
+        let selected = options
+            .iter()
+            .position(|option| option.as_deref() == default && default.is_some())
+            .unwrap_or(0);
+
+        ProfilePicker {
+            local,
+            options,
+            selected,
+        }
+    }
+
+    pub fn selected(&self) -> Option<&str> {
+        self.options[self.selected].as_deref()
+    }
+
+    pub fn increment_active_selection(&mut self) {
+        self.selected = (self.selected + 1) % self.options.len();
+    }
+
+    pub fn decrement_active_selection(&mut self) {
+        self.selected = self
+            .selected
+            .checked_sub(1)
+            .unwrap_or(self.options.len() - 1);
+    }
+}
+
+impl Widget for &ProfilePicker {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [area] = Layout::vertical([Constraint::Length(self.options.len() as u16 + 4)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::horizontal([Constraint::Percentage(40)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        let block = Block::bordered()
+            .title(format!(" launch {} ", self.local.blender_version.version))
+            .title_bottom(" enter launch | esc cancel ")
+            .border_set(border::ROUNDED)
+            .padding(Padding::uniform(1))
+            .magenta();
+
+        let lines: Vec<Line> = self
+            .options
+            .iter()
+            .enumerate()
+            .map(|(idx, option)| {
+                let line = Line::from(option.as_deref().unwrap_or("no profile").to_owned());
+
+                match idx == self.selected {
+                    true => line.style(Style::default().bg(Color::DarkGray)),
+                    false => line,
+                }
+            })
+            .collect();
+
+        Clear.render(area, buf);
+        Paragraph::new(Text::from(lines))
+            .block(block)
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,233 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    prelude::{Buffer, Rect, Stylize},
+    style::{Color, Style},
+    symbols::border,
+    widgets::{Block, Gauge, Padding, Widget},
+};
+
+use crate::{
+    tui::{JobEvent, JobId},
+    BlenderVersion, ExtractProgress, Progress,
+};
+
+use super::{ActiveWidget, StateRef};
+
+pub enum JobState {
+    Queued,
+    Active,
+    Paused,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl JobState {
+    pub fn is_running(&self) -> bool {
+        matches!(self, JobState::Queued | JobState::Active | JobState::Paused)
+    }
+}
+
+pub struct Job {
+    id: JobId,
+    version: BlenderVersion,
+    state: JobState,
+    progress: Option<Progress>,
+    extracting: Option<ExtractProgress>,
+    status: String,
+}
+
+/// Most jobs shown at once, older ones scroll out of view.
+const MAX_VISIBLE: usize = 8;
+
+pub struct QueueWidget {
+    state: StateRef,
+
+    jobs: Vec<Job>,
+    selected: usize,
+}
+
+impl QueueWidget {
+    pub fn new(state: StateRef) -> Self {
+        QueueWidget {
+            state,
+
+            jobs: Vec::new(),
+            selected: 0,
+        }
+    }
+
+    pub fn update(&mut self, id: JobId, event: JobEvent) {
+        if let JobEvent::Queued(version) = event {
+            self.jobs.push(Job {
+                id,
+                version,
+                state: JobState::Queued,
+                progress: None,
+                extracting: None,
+                status: "queued".to_owned(),
+            });
+            return;
+        }
+
+        let Some(job) = self.jobs.iter_mut().find(|job| job.id == id) else {
+            return;
+        };
+
+        match event {
+            JobEvent::Queued(_) => {}
+            JobEvent::Active => {
+                job.state = JobState::Active;
+                job.status = "starting".to_owned();
+            }
+            JobEvent::Paused => {
+                job.state = JobState::Paused;
+                job.status = "paused".to_owned();
+            }
+            JobEvent::Progress(progress) => {
+                job.status = format!(
+                    "{} | {} | estimated {}",
+                    progress.speed, progress.elapsed, progress.estimated
+                );
+                job.progress = Some(progress);
+            }
+            JobEvent::Extracting(progress) => {
+                job.status = format!(
+                    "extracting {} entries, {:.1}mb",
+                    progress.entries,
+                    progress.bytes as f32 / 1000000.0
+                );
+                job.extracting = Some(progress);
+            }
+            JobEvent::Status(status) => job.status = status,
+            JobEvent::Retry(retry) => job.status = retry.to_string(),
+            JobEvent::Done(_) => {
+                job.state = JobState::Done;
+                job.status = "done".to_owned();
+            }
+            JobEvent::Failed(err) => {
+                job.state = JobState::Failed;
+                job.status = err;
+            }
+            JobEvent::Cancelled => {
+                job.state = JobState::Cancelled;
+                job.status = "cancelled".to_owned();
+            }
+        }
+    }
+
+    /// Directory name of the build job `id` installs.
+    pub fn name(&self, id: JobId) -> Option<String> {
+        self.jobs
+            .iter()
+            .find(|job| job.id == id)
+            .map(|job| job.version.dir_name())
+    }
+
+    pub fn has_running(&self) -> bool {
+        self.jobs.iter().any(|job| job.state.is_running())
+    }
+
+    /// Id and state of the selected job.
+    pub fn selected(&self) -> Option<(JobId, &JobState)> {
+        self.visible()
+            .get(self.selected)
+            .map(|job| (job.id, &job.state))
+    }
+
+    pub fn increment_active_selection(&mut self) {
+        self.selected += 1;
+
+        if self.selected >= self.visible().len() {
+            self.selected = 0;
+        }
+    }
+
+    pub fn decrement_active_selection(&mut self) {
+        let len = self.visible().len();
+        if len == 0 {
+            return;
+        }
+
+        if self.selected == 0 {
+            self.selected = len - 1;
+        } else {
+            self.selected = self.selected.saturating_sub(1);
+        }
+    }
+
+    /// Rows needed to show every job, including the border.
+    pub fn height(&self) -> u16 {
+        if self.jobs.is_empty() {
+            return 0;
+        }
+        self.jobs.len().min(MAX_VISIBLE) as u16 + 2
+    }
+
+    fn visible(&self) -> &[Job] {
+        &self.jobs[self.jobs.len().saturating_sub(MAX_VISIBLE)..]
+    }
+}
+
+impl Widget for &QueueWidget {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut block = Block::bordered()
+            .title(" queue ")
+            .border_set(border::ROUNDED)
+            .padding(Padding::horizontal(1));
+
+        match self.state.read().unwrap().active_widget {
+            ActiveWidget::QueueWidget => {
+                block = block.magenta();
+            }
+            _ => {
+                block = block.cyan();
+            }
+        }
+
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(self.visible().iter().map(|_| Constraint::Length(1)))
+            .split(inner);
+
+        for (idx, (job, row)) in self.visible().iter().zip(rows.iter()).enumerate() {
+            let color = match job.state {
+                JobState::Queued => Color::Gray,
+                JobState::Active => Color::Cyan,
+                JobState::Paused => Color::Yellow,
+                JobState::Done => Color::Green,
+                JobState::Failed | JobState::Cancelled => Color::Red,
+            };
+
+            let background = match idx == self.selected {
+                true => Color::DarkGray,
+                false => Color::Black,
+            };
+
+            let ratio = match (&job.state, &job.extracting, &job.progress) {
+                (JobState::Done, _, _) => 1.0,
+                (_, Some(extracting), _) => extracting.ratio(),
+                (_, None, Some(progress)) => progress.ratio(),
+                _ => 0.0,
+            };
+
+            let label = format!(
+                "{} {} {} {:>5.1}% {}",
+                job.version.version,
+                job.version.release,
+                job.version.branch,
+                ratio * 100.0,
+                job.status
+            );
+
+            Gauge::default()
+                .gauge_style(Style::default().fg(color).bg(background))
+                .ratio(ratio)
+                .label(label)
+                .render(*row, buf);
+        }
+    }
+}
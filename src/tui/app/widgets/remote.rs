@@ -1,6 +1,17 @@
-use std::{fs::File, path::PathBuf, str::FromStr};
+use std::{
+    collections::HashMap,
+    fs::File,
+    path::{Path, PathBuf},
+};
 
-use crate::{config::Config, BlenderVersion};
+use crate::{
+    blender_utils::{remote_status, RemoteStatus},
+    config::Config,
+    extract::{ExtractError, ExtractProgress},
+    manager::JobControl,
+    tui::{Message, TxMessage},
+    BlenderVersion, Listing, LocalBlenderVersion, Release, Retry, Version,
+};
 use ratatui::{
     layout::{Constraint, Direction, Layout},
     prelude::{Buffer, Rect, Stylize},
@@ -10,6 +21,8 @@ use ratatui::{
     widgets::{Block, Padding, Paragraph, Widget},
 };
 
+use tokio::sync::watch::Receiver;
+
 use super::StateRef;
 
 pub struct RemoteWidget {
@@ -21,6 +34,11 @@ pub struct RemoteWidget {
     available: Vec<BlenderVersion>,
     len: usize,
 
+    locals: Vec<LocalBlenderVersion>,
+
+    /// Platform and architecture the list shows, either may be "all".
+    filter: (String, String),
+
     selected: usize,
 
     message: String,
@@ -28,6 +46,11 @@ pub struct RemoteWidget {
 
 impl RemoteWidget {
     pub fn new(state: StateRef) -> Self {
+        let filter = {
+            let config = &state.read().unwrap().config;
+            (config.platform(), config.arch())
+        };
+
         RemoteWidget {
             state,
 
@@ -37,50 +60,64 @@ impl RemoteWidget {
             len: 0,
             available: Vec::new(),
 
+            locals: Vec::new(),
+
+            filter,
+
             selected: 0,
             message: "press enter to check available versions".into(),
         }
     }
 }
 
-pub async fn get_links(config: Config) -> Result<Vec<BlenderVersion>, String> {
-    match crate::getter::get_links(&config).await {
+/// Lists the remote builds, reporting retried requests in the status box.
+pub async fn get_links(config: Config, tx: TxMessage) -> Result<Listing, String> {
+    let on_retry = |retry: &Retry| {
+        let _ = tx.try_send(Message::VersionUpdate(format!("listing: {retry}")));
+    };
+
+    match crate::getter::get_links(&config, &on_retry).await {
         Ok(versions) => Ok(versions),
         Err(err) => Err(err.to_string()),
     }
 }
 
-pub fn get_file(version: &BlenderVersion, config: Config) -> (File, PathBuf) {
-    let filename = version.link.split("daily/").nth(1).unwrap();
-
-    let mut path = PathBuf::from_str(&config.path).unwrap();
-    path.push(filename);
+pub fn get_file(version: &BlenderVersion, config: Config) -> Result<(File, PathBuf), String> {
+    let mut path = PathBuf::from(&config.path);
+    path.push(version.file_name());
 
     // if downloaded.contains(&path.with_extension("").with_extension("")) {
     //     println!("{} Already at Latest version", version.version);
     // }
 
-    if path.exists() {
-        println!("{} Already downloaded", version.version);
+    // a finished but unextracted archive is verified again like a partial download
+    let part = crate::getter::part_path(&path);
+    if path.exists() && !part.exists() {
+        std::fs::rename(&path, &part).map_err(|err| err.to_string())?;
     }
 
-    let file = std::fs::File::create(&path).unwrap();
-    (file, path)
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .append(true)
+        .create(true)
+        .open(&part)
+        .map_err(|err| format!("could not open {}: {err}", part.display()))?;
+    Ok((file, path))
 }
 
-pub fn extract_and_clean(path: PathBuf, config: &Config) {
-    let mut child = std::process::Command::new("tar")
-        .arg("-xf")
-        .arg(&path)
-        .arg(format!("--directory={}", config.path))
-        .spawn()
-        .unwrap();
-
-    let result = child.wait().unwrap();
-
-    if result.success() {
-        std::fs::remove_file(&path).unwrap();
-    }
+/// Extracts the archive at `path` into `target` and removes it, stopping early when the job
+/// is cancelled.
+pub fn extract_and_clean(
+    path: PathBuf,
+    target: &Path,
+    control: &Receiver<JobControl>,
+    report: impl FnMut(ExtractProgress),
+) -> Result<(), ExtractError> {
+    let cancelled = || *control.borrow() == JobControl::Cancel;
+    crate::extract::extract(&path, target, cancelled, report)?;
+
+    std::fs::remove_file(&path)?;
+    Ok(())
 }
 
 impl RemoteWidget {
@@ -105,21 +142,91 @@ impl RemoteWidget {
     }
 
     pub fn set_available(&mut self, links: Vec<BlenderVersion>) {
-        self.len = links.len();
         self.select_mode = true;
         self.checked = true;
         self.available = links;
+        self.len = self.visible().len();
+        self.selected = self.selected.min(self.len.saturating_sub(1));
         self.set_message("ready");
     }
 
+    /// Builds matching the platform filter.
+    fn visible(&self) -> Vec<&BlenderVersion> {
+        let (os, arch) = &self.filter;
+        self.available
+            .iter()
+            .filter(|version| version.targets(os, arch))
+            .collect()
+    }
+
+    /// Switches the filter to the next platform in the listing, then to all of them.
+    pub fn cycle_filter(&mut self) {
+        let mut filters: Vec<(String, String)> = self
+            .available
+            .iter()
+            .map(|version| (version.os.clone(), version.arch.clone()))
+            .collect();
+        filters.sort();
+        filters.dedup();
+        filters.push(("all".to_owned(), "all".to_owned()));
+
+        let next = filters
+            .iter()
+            .position(|filter| *filter == self.filter)
+            .map(|idx| (idx + 1) % filters.len())
+            .unwrap_or(0);
+
+        self.filter = filters.swap_remove(next);
+        self.len = self.visible().len();
+        self.selected = 0;
+        self.set_message(format!(
+            "showing {} {} builds",
+            self.filter.0, self.filter.1
+        ));
+    }
+
+    /// Installs the remote builds are compared against.
+    pub fn set_local(&mut self, locals: Vec<LocalBlenderVersion>) {
+        self.locals = locals;
+    }
+
+    /// Remote builds that update an installed version and branch, the newest one of each
+    /// when several sources list one.
+    pub fn outdated(&self) -> Vec<BlenderVersion> {
+        let mut newest: HashMap<(Version, &str, &str, &str), &BlenderVersion> = HashMap::new();
+
+        for version in self.visible() {
+            if remote_status(version, &self.locals) != RemoteStatus::Update {
+                continue;
+            }
+
+            let key = (
+                version.version,
+                version.branch.as_str(),
+                version.os.as_str(),
+                version.arch.as_str(),
+            );
+            newest
+                .entry(key)
+                .and_modify(|current| {
+                    if version.build_date > current.build_date {
+                        *current = version;
+                    }
+                })
+                .or_insert(version);
+        }
+
+        newest.into_values().cloned().collect()
+    }
+
     pub fn set_message(&mut self, message: impl ToString) {
         self.message = message.to_string();
     }
 
-    pub fn download_selected(&mut self) -> BlenderVersion {
-        let selected = self.available.get(self.selected).unwrap().clone();
+    pub fn download_selected(&mut self) -> Option<BlenderVersion> {
+        let selected = self.visible().get(self.selected).copied()?.clone();
         self.set_message(format!("downloading {}", selected.link));
-        selected
+        Some(selected)
     }
 }
 
@@ -144,51 +251,74 @@ impl Widget for &RemoteWidget {
 
         p.render(layout[1], buf);
 
+        let (os, arch) = &self.filter;
         let mut block = Block::bordered()
-            .title(" remote ")
+            .title(format!(" remote ({os} {arch}) "))
             .border_set(border::ROUNDED)
             .padding(Padding::uniform(1));
 
         match self.state.read().unwrap().active_widget {
-            super::ActiveWidget::FileListWidget => {
-                block = block.cyan();
-            }
             super::ActiveWidget::RemoteWidget => {
                 block = block.magenta();
             }
+            _ => {
+                block = block.cyan();
+            }
         }
 
         let lines: Vec<Line> = self
-            .available
-            .iter()
+            .visible()
+            .into_iter()
             .enumerate()
             .map(|(idx, version)| {
-                let version_span = match &version.version {
-                    x if x.contains("4.2") => {
-                        Span::styled(format!("{x:^10}"), Style::default().bg(Color::Green))
-                    }
-                    x if x.contains("4.3") => {
-                        Span::styled(format!("{x:^10}"), Style::default().bg(Color::Magenta))
-                    }
-                    x => Span::styled(format!("{x:^10}"), Style::default().bg(Color::Gray)),
+                let x = version.version.to_string();
+                let version_span = match version.version.series().as_str() {
+                    "4.2" => Span::styled(format!("{x:^10}"), Style::default().bg(Color::Green)),
+                    "4.3" => Span::styled(format!("{x:^10}"), Style::default().bg(Color::Magenta)),
+                    _ => Span::styled(format!("{x:^10}"), Style::default().bg(Color::Gray)),
                 };
 
-                let release_span = match version.release.as_str() {
-                    x if x == "stable" => {
+                let x = version.release;
+                let release_span = match x {
+                    Release::Stable => {
                         Span::styled(format!("{x:^10}"), Style::default().fg(Color::Green))
                     }
-                    x if x == "beta" => {
+                    Release::Rc | Release::Candidate => {
+                        Span::styled(format!("{x:^10}"), Style::default().fg(Color::Yellow))
+                    }
+                    Release::Beta => {
                         Span::styled(format!("{x:^10}"), Style::default().fg(Color::Magenta))
                     }
-                    x if x == "alpha" => {
+                    Release::Alpha => {
                         Span::styled(format!("{x:^10}"), Style::default().fg(Color::Gray))
                     }
-                    _ => Span::styled(String::new(), Style::default().fg(Color::Red)),
                 };
 
-                let branch_span = Span::raw(&version.branch);
+                let branch_span = Span::raw(format!("{:<12}", version.branch));
+                let source_span = Span::raw(format!(" {:<12}", version.source));
+                let platform_span = Span::raw(format!(
+                    "{:<14}",
+                    format!("{} {}", version.os, version.arch)
+                ));
+
+                let status_span = match remote_status(version, &self.locals) {
+                    RemoteStatus::Installed => {
+                        Span::styled("installed", Style::default().fg(Color::Green))
+                    }
+                    RemoteStatus::Update => {
+                        Span::styled("update", Style::default().fg(Color::Yellow))
+                    }
+                    RemoteStatus::New => Span::styled("new", Style::default().fg(Color::Cyan)),
+                };
 
-                let mut line = Line::from(vec![version_span, release_span, branch_span]);
+                let mut line = Line::from(vec![
+                    version_span,
+                    release_span,
+                    branch_span,
+                    source_span,
+                    platform_span,
+                    status_span,
+                ]);
                 if idx == self.selected {
                     line = line
                         .into_iter()
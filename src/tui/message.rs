@@ -1,16 +1,49 @@
 use std::{path::PathBuf, sync::Arc};
 use tokio::sync::mpsc::Sender;
-use crate::BlenderVersion;
+use crate::{prune::PruneReport, BlenderVersion, ExtractProgress, Progress, Retry};
 
 
 pub type TxMessage = Arc<Sender<Message>>;
+pub type JobId = usize;
+pub type InstanceId = usize;
+
 pub enum Message {
     Links(Vec<BlenderVersion>),
 
     VersionUpdate(String),
-    VersionResult(PathBuf),
-    
-    ExtractResult,
+
+    Job(JobId, JobEvent),
+
+    /// Bytes removed out of the total while deleting an install.
+    RemoveProgress(u64, u64),
+    Removed(Result<PathBuf, String>),
+    Pruned(Result<PruneReport, String>),
+
+    Instance(InstanceId, InstanceEvent),
 
     Error(String),
 }
+
+pub enum JobEvent {
+    Queued(BlenderVersion),
+    Active,
+    Paused,
+    Progress(Progress),
+    Extracting(ExtractProgress),
+    Status(String),
+    /// A request failed and is tried again.
+    Retry(Retry),
+    Done(PathBuf),
+    Failed(String),
+    Cancelled,
+}
+
+pub enum InstanceEvent {
+    Started {
+        pid: u32,
+        version: Box<BlenderVersion>,
+        log: PathBuf,
+    },
+    /// Exit status of the process.
+    Exited(String),
+}
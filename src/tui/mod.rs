@@ -2,6 +2,9 @@ mod app;
 mod message;
 mod utils;
 
-pub use message::{Message, TxMessage};
-pub use app::TuiApp;
+pub use message::{InstanceEvent, InstanceId, JobEvent, JobId, Message, TxMessage};
+pub use app::{
+    check_downloaded, extract_and_clean, get_file, get_links, parse_downloaded, remove_install,
+    TuiApp,
+};
 pub use utils::{init, restore};
@@ -0,0 +1,117 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A `major.minor.patch` Blender version, ordered numerically.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl Version {
+    pub fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Version {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Whether this version belongs to a series such as "4", "4.2" or "4.2.1".
+    pub fn in_series(&self, series: &str) -> bool {
+        let parts = [self.major, self.minor, self.patch];
+
+        let mut matched = 0;
+        for (idx, part) in series.split('.').enumerate() {
+            match (parts.get(idx), part.parse::<u32>()) {
+                (Some(own), Ok(part)) if *own == part => matched += 1,
+                _ => return false,
+            }
+        }
+
+        matched > 0
+    }
+
+    /// The `major.minor` series this version belongs to.
+    pub fn series(&self) -> String {
+        format!("{}.{}", self.major, self.minor)
+    }
+}
+
+impl FromStr for Version {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('.').map(|part| part.parse::<u32>());
+
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) => {
+                Ok(Version::new(major, minor, patch))
+            }
+            _ => Err(format!("invalid version {s}, expected major.minor.patch")),
+        }
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl Serialize for Version {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Release type of a build, ordered from least to most stable.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Release {
+    Alpha,
+    Beta,
+    Rc,
+    Candidate,
+    #[default]
+    Stable,
+}
+
+impl FromStr for Release {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "alpha" => Ok(Release::Alpha),
+            "beta" => Ok(Release::Beta),
+            "rc" => Ok(Release::Rc),
+            "candidate" => Ok(Release::Candidate),
+            "stable" => Ok(Release::Stable),
+            _ => Err(format!("unknown release type {s}")),
+        }
+    }
+}
+
+impl Display for Release {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Release::Alpha => "alpha",
+            Release::Beta => "beta",
+            Release::Rc => "rc",
+            Release::Candidate => "candidate",
+            Release::Stable => "stable",
+        };
+        f.write_str(s)
+    }
+}